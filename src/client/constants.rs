@@ -56,6 +56,7 @@ pub const YIELD_ICON_HEIGHT: f32 = 16.0;
 pub const SPRITE_YIELD_FOOD: usize = 0;
 pub const SPRITE_YIELD_PRODUCTION: usize = 1;
 pub const SPRITE_YIELD_SCIENCE: usize = 2;
+pub const SPRITE_YIELD_GOLD: usize = 3;
 
 // Citizens spritesheet
 pub const CITIZEN_ICON_WIDTH: f32 = 100.0;
@@ -44,6 +44,11 @@ impl Hitbox {
         }
     }
 
+    // Sits above the plain tile hitbox so that clicking it buys the tile instead of selecting it.
+    pub fn purchasable_tile(pos: TilePosition) -> Self {
+        Self { z: 0.5, ..Self::tile(pos) }
+    }
+
     pub fn unit(pos: TilePosition, unit_type: UnitType) -> Self {
         match unit_type {
             UnitType::Civilian => Self::civilian(get_tile_window_pos(pos)),
@@ -102,6 +107,7 @@ pub enum HitboxKey {
     Tile(TilePosition),
     Unit(UnitId),
     Citizen(TilePosition),
+    PurchasableTile(TilePosition),
 }
 
 pub fn get_hovered_object<'a>(
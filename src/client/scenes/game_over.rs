@@ -0,0 +1,108 @@
+use ggez::graphics;
+use ggez::graphics::Rect;
+use ggez::event::KeyCode;
+
+use crate::client::scene::{Scene, SceneSwitch};
+
+use crate::client::{InputEvent, SharedData};
+use crate::client::imgui_wrapper::ImGuiFonts;
+use crate::common::{GameWorld, PlayerId};
+
+pub struct GameOverState {
+    world: GameWorld,
+    player_id: PlayerId,
+    closing: bool,
+}
+
+impl GameOverState {
+    pub fn new(world: GameWorld, player_id: PlayerId) -> Self {
+        Self {
+            world,
+            player_id,
+            closing: false,
+        }
+    }
+}
+
+impl Scene<SharedData, InputEvent> for GameOverState {
+    fn update(&mut self, _shared_data: &mut SharedData, _ctx: &mut ggez::Context) -> SceneSwitch<SharedData, InputEvent> {
+        // Goes back to the map, which can still be looked around.
+        if self.closing {
+            return SceneSwitch::Pop;
+        }
+
+        SceneSwitch::None
+    }
+
+    fn draw(&mut self, shared_data: &mut SharedData, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        graphics::clear(ctx, graphics::Color::BLACK);
+
+        let Rect { w: screen_width, h: screen_height, .. } = graphics::screen_coordinates(ctx);
+
+        let Self { world, player_id, closing } = self;
+        let outcome = world.game_over().unwrap();
+        let you_civ_id = world.player(*player_id).unwrap().civilization_id();
+
+        let func = |ui: &imgui::Ui, _fonts: &ImGuiFonts| {
+            use imgui::*;
+
+            let window_width = ui.current_font_size() * 22.0;
+            let window_height = ui.current_font_size() * 20.0;
+            let full_button_size: [f32; 2] = [window_width - ui.clone_style().window_padding[0] * 2.0, ui.current_font_size() * 2.0];
+
+            imgui::Window::new(im_str!("Game Over"))
+                .position([(screen_width - window_width) / 2.0, (screen_height - window_height) / 2.0], Condition::Always)
+                .size([window_width, window_height], Condition::Always)
+                .collapsible(false)
+                .resizable(false)
+                .build(ui, || {
+                    if outcome.winners.contains(&you_civ_id) {
+                        ui.text("You have won!");
+                    } else {
+                        ui.text("You have lost.");
+                    }
+
+                    let winner_names: Vec<_> = outcome.winners.iter()
+                        .map(|id| world.civilization(*id).unwrap().player_name().clone())
+                        .collect();
+                    ui.text(format!("{} victory on turn {}: {}", outcome.victory_type, world.turn(), winner_names.join(", ")));
+
+                    ui.spacing();
+                    ui.separator();
+                    ui.spacing();
+
+                    for player in world.players() {
+                        let civilization = world.civilization(player.civilization_id()).unwrap();
+                        let mut text = format!("{}: {} points", civilization.player_name(), world.civilization_score(civilization.id()));
+                        if civilization.eliminated() {
+                            text += " (eliminated)";
+                        }
+                        ui.text(text);
+                    }
+
+                    ui.spacing();
+                    ui.separator();
+                    ui.spacing();
+                    *closing = ui.button(im_str!("Return to map"), full_button_size);
+                });
+        };
+
+        shared_data.imgui_wrapper.render(ctx, shared_data.hidpi_factor, func);
+
+        graphics::present(ctx)
+    }
+
+    fn input(&mut self, shared_data: &mut SharedData, event: InputEvent, _started: bool) {
+        if shared_data.imgui_wrapper.handle_event(&event) {
+            return;
+        }
+
+        if let InputEvent::KeyUpEvent { code: KeyCode::Escape, .. } = event {
+            self.closing = true;
+        }
+    }
+
+    fn name(&self) -> &str {
+        "GameOverState"
+    }
+}
@@ -22,16 +22,26 @@ use crate::common::{
     Citizen,
     Unit,
     UnitAbility,
+    UnitOrder,
     ProducingItemId,
+    DiplomaticState,
+    YieldValue,
+    CITY_STATE_GOLD_GIFT,
+    Statistic,
 };
 
 use crate::client::scenes::in_game::InGameState;
 use crate::client::constants::*;
 use crate::client::imgui_wrapper::ImGuiRenderContext;
 use crate::client::selected_object::SelectedObject;
+use crate::client::hitbox::{HitboxKey, get_hovered_object};
 use crate::client::utils::get_tile_window_pos;
 
 const CENTER_OFFSET: mint::Point2<f32> = mint::Point2 { x: 0.5, y: 0.5 };
+const MOVE_PATH_COLOR: graphics::Color = graphics::Color::new(1.0, 0.85, 0.3, 0.8);
+const PURCHASABLE_TILE_COLOR: graphics::Color = graphics::Color::new(1.0, 0.8, 0.0, 0.5);
+const UNAFFORDABLE_TILE_COLOR: graphics::Color = graphics::Color::new(0.6, 0.6, 0.6, 0.4);
+const BARBARIAN_CAMP_COLOR: graphics::Color = graphics::Color::new(0.8, 0.1, 0.1, 0.6);
 
 fn get_tile_image_src_rect(index: usize) -> Rect {
     get_image_src_rect(index, 10, 8)
@@ -181,6 +191,7 @@ impl InGameState {
             (SPRITE_YIELD_FOOD, yields.food),
             (SPRITE_YIELD_PRODUCTION, yields.production),
             (SPRITE_YIELD_SCIENCE, yields.science),
+            (SPRITE_YIELD_GOLD, yields.gold),
         ];
         for &(sprite_index, yield_value) in yield_types {
             let yield_value = yield_value.round();
@@ -250,6 +261,12 @@ impl InGameState {
         }
     }
 
+    pub(super) fn draw_barbarian_camps(&self, ctx: &mut Context) {
+        for camp in self.world.barbarian_camps() {
+            self.draw_tile_sprite(ctx, camp.position, SPRITE_TILE_HIGHLIGHT, Some(BARBARIAN_CAMP_COLOR));
+        }
+    }
+
     pub(super) fn draw_tiles_yields(&self, ctx: &mut Context) {
         for tile in self.world.map.tiles() {
             self.draw_yields(ctx, tile.position, tile.yields());
@@ -308,6 +325,12 @@ impl InGameState {
                             self.draw_citizen_sprite(ctx, *pos, *citizen);
                         }
                     }
+
+                    let gold = self.world.civilization(city.owner()).unwrap().gold();
+                    for (pos, cost) in self.purchasable_tiles(city) {
+                        let color = if cost <= gold { PURCHASABLE_TILE_COLOR } else { UNAFFORDABLE_TILE_COLOR };
+                        self.draw_tile_sprite(ctx, pos, SPRITE_TILE_HIGHLIGHT, Some(color));
+                    }
                 }
                 SelectedObject::Unit(unit_id) => {
                     let unit = self.world.unit(*unit_id).unwrap();
@@ -319,17 +342,19 @@ impl InGameState {
 
                     self.draw_tile_sprite(ctx, position, sprite_index, None);
 
+                    for path_position in unit.move_path() {
+                        self.draw_tile_sprite(ctx, *path_position, SPRITE_TILE_HIGHLIGHT_BLUE_1, Some(MOVE_PATH_COLOR));
+                    }
+
                     if self.can_control_unit(unit) && ggez::input::mouse::button_pressed(ctx, MouseButton::Right) {
-                        let neighbor_map = position.neighbors_at_distance(
-                            self.world.map.width(),
-                            self.world.map.height(),
-                            unit.remaining_movement(),
-                            true,
-                        );
-
-                        for (neighbor, distance) in neighbor_map {
-                            let sprite_index = match distance {
-                                0 => continue,
+                        let reachable_tiles = self.world.reachable_tiles(unit);
+
+                        for (neighbor, remaining_movement) in reachable_tiles {
+                            if neighbor == position {
+                                continue;
+                            }
+
+                            let sprite_index = match unit.remaining_movement() - remaining_movement {
                                 1 => SPRITE_TILE_HIGHLIGHT_BLUE_1,
                                 2 => SPRITE_TILE_HIGHLIGHT_BLUE_2,
                                 _ => SPRITE_TILE_HIGHLIGHT_BLUE_3,
@@ -400,7 +425,9 @@ impl InGameState {
                 for player in self.world.players() {
                     let you_str = if player.id() == self.player_id { " (you)" } else { "" };
                     let ready_str = if player.ready() { " (ready)" } else { "" };
-                    rc.ui.text(format!("{}{}{}", player.name(), you_str, ready_str));
+                    let eliminated = self.world.civilization(player.civilization_id()).unwrap().eliminated();
+                    let eliminated_str = if eliminated { " (eliminated)" } else { "" };
+                    rc.ui.text(format!("{}{}{}{}", player.name(), you_str, ready_str, eliminated_str));
                 }
                 rc.ui.spacing();
                 rc.ui.separator();
@@ -409,6 +436,30 @@ impl InGameState {
                 rc.ui.spacing();
 
                 rc.ui.text(format!("{}", self.world.civilization_science_yield(you_civ_id)));
+                let gold = self.world.civilization(you_civ_id).unwrap().gold();
+                rc.ui.text(format!("{} Gold ({})", gold, self.world.civilization_gold_yield(you_civ_id)));
+                rc.ui.text(format!("Upkeep: {} Gold", self.world.civilization_maintenance(you_civ_id)));
+                let strategic_income = self.world.civilization_strategic_resource_income(you_civ_id);
+                let stock = self.world.civilization(you_civ_id).unwrap().strategic_resources();
+                let mut strategic_resources: Vec<_> = stock.keys().chain(strategic_income.keys()).collect();
+                strategic_resources.sort();
+                strategic_resources.dedup();
+                for resource in strategic_resources {
+                    rc.ui.text(format!(
+                        "{}: {} (+{})",
+                        resource,
+                        stock.get(resource).unwrap_or(&0),
+                        strategic_income.get(resource).unwrap_or(&0),
+                    ));
+                }
+                let happiness = self.world.civilization_happiness(you_civ_id);
+                rc.ui.text(format!("Happiness: {}", happiness));
+                if happiness < 0 {
+                    rc.ui.text_wrapped(im_str!("Cities won't grow while your civilization is unhappy"));
+                }
+                if gold < 0.0.into() {
+                    rc.ui.text_wrapped(im_str!("Units and then buildings will be lost until the treasury is positive"));
+                }
 
                 rc.ui.spacing();
                 rc.ui.separator();
@@ -418,6 +469,18 @@ impl InGameState {
                     self.display_tech_tree = true;
                 }
 
+                if rc.ui.button(im_str!("Diplomacy"), button_size) {
+                    self.display_diplomacy = true;
+                }
+
+                if rc.ui.button(im_str!("Statistics"), button_size) {
+                    self.display_statistics = true;
+                }
+
+                if rc.ui.button(im_str!("Export Map"), button_size) {
+                    self.export_map();
+                }
+
                 rc.ui.spacing();
                 rc.ui.separator();
                 rc.ui.spacing();
@@ -435,6 +498,13 @@ impl InGameState {
                     }
                 }
 
+                if self.world.diplomacy().proposals_to(you_civ_id).next().is_some() {
+                    todo_something = true;
+                    if rc.ui.button(im_str!("Answer proposals"), button_size) {
+                        self.display_diplomacy = true;
+                    }
+                }
+
                 for city in self.world.cities().filter(|city| city.owner() == you_civ_id) {
                     if city.producing().is_none() {
                         todo_something = true;
@@ -442,11 +512,25 @@ impl InGameState {
                         if clicked {
                             self.selected = Some(SelectedObject::City(city.id(), ImString::new(city.name())));
                         }
+                    } else if self.world.production_stalled(city.id()) {
+                        todo_something = true;
+                        let clicked = rc.ui.button(&ImString::new(format!("Prod stalled in {}", city.name())), button_size);
+                        if clicked {
+                            self.selected = Some(SelectedObject::City(city.id(), ImString::new(city.name())));
+                        }
+                    }
+                }
+
+                for unit in self.world.units().filter(|unit| unit.owner() == you_civ_id && unit.can_be_promoted()) {
+                    todo_something = true;
+                    let clicked = rc.ui.button(&ImString::new(format!("Promote {} {}", unit.name(), unit.position())), button_size);
+                    if clicked {
+                        self.selected = Some(SelectedObject::Unit(unit.id()));
                     }
                 }
 
                 for unit in self.world.units().filter(|unit| unit.owner() == you_civ_id) {
-                    if !unit.sleeping() && unit.remaining_movement() > 0 {
+                    if unit.order().is_none() && !unit.has_move_order() && unit.improvement_order().is_none() && unit.remaining_movement() > 0 {
                         todo_something = true;
                         let clicked = rc.ui.button(&ImString::new(format!("Move {} {}", unit.name(), unit.position())), button_size);
                         if clicked {
@@ -463,7 +547,11 @@ impl InGameState {
                 rc.ui.separator();
                 rc.ui.spacing();
 
-                rc.ui.text(format!("Turn {}", self.world.turn()));
+                if let Some(turn_limit) = self.world.victory_conditions().turn_limit {
+                    rc.ui.text(format!("Turn {}/{}", self.world.turn(), turn_limit));
+                } else {
+                    rc.ui.text(format!("Turn {}", self.world.turn()));
+                }
                 let open_sans_semi_bold_30_handle = rc.ui.push_font(rc.fonts.open_sans_semi_bold_30);
                 let you_ready = self.world.player(self.player_id).unwrap().ready();
                 let turn_button_label = if you_ready { im_str!("Waiting for players") } else { im_str!("Next turn") };
@@ -483,6 +571,18 @@ impl InGameState {
 
         let mut hover_text = None;
 
+        if let Some(SelectedObject::City(city_id, _)) = self.selected {
+            let city = self.world.city(city_id).unwrap();
+            let mint::Point2 { x: mouse_x, y: mouse_y } = ggez::input::mouse::position(ctx);
+            if !rc.ui.io().want_capture_mouse {
+                if let Some(HitboxKey::Tile(pos)) = get_hovered_object(mouse_x, mouse_y, self.zoom, &self.offset, &self.hitboxes) {
+                    if let Some((_, cost)) = self.purchasable_tiles(city).into_iter().find(|(tile, _)| *tile == pos) {
+                        hover_text = Some(format!("Buy tile for {} gold", cost));
+                    }
+                }
+            }
+        }
+
         use imgui::*;
 
         let Rect { w: screen_width, h: screen_height, .. } = graphics::screen_coordinates(ctx);
@@ -510,6 +610,16 @@ impl InGameState {
                         if let Some(vegetation) = tile.vegetation {
                             things.push(format!("{}", vegetation));
                         }
+                        if let Some(improvement) = &tile.improvement {
+                            things.push(improvement.name.clone());
+                        }
+                        if self.world.barbarian_camp(*pos).is_some() {
+                            things.push("Barbarian camp".into());
+                        }
+                        let you_civ_id = self.world.player(self.player_id).unwrap().civilization_id();
+                        if self.world.in_enemy_zone_of_control(*pos, you_civ_id) {
+                            things.push("Enemy zone of control".into());
+                        }
                         if things.len() > 0 {
                             rc.ui.text(things.join(", "));
                         }
@@ -526,14 +636,35 @@ impl InGameState {
                         if yields.science > 0.0.into() {
                             rc.ui.text(format!("{} science", yields.science));
                         }
+                        if yields.gold > 0.0.into() {
+                            rc.ui.text(format!("{} gold", yields.gold));
+                        }
                     },
                     SelectedObject::Unit(unit_id) => {
                         let unit = self.world.unit(*unit_id).unwrap();
                         let owner_name = self.world.civilization(unit.owner()).unwrap().player_name();
                         rc.ui.text(format!("{} at {}", unit.name(), unit.position()));
                         rc.ui.text(format!("Type: {}", unit.unit_type()));
+                        rc.ui.text(format!("Domain: {}", unit.domain()));
                         rc.ui.text(format!("Owner: {}", owner_name));
                         rc.ui.text(format!("Movement: {}/{}", unit.remaining_movement(), unit.total_movement()));
+                        rc.ui.text(format!("Health: {}/{}", unit.health(), unit.max_health()));
+                        if unit.can_fight() {
+                            rc.ui.text(format!("Strength: {}", unit.strength()));
+                            rc.ui.text(format!("Experience: {}/{}", unit.experience(), unit.required_experience_for_promotion()));
+                            for promotion in unit.promotions() {
+                                rc.ui.text(format!("- {}", promotion.name));
+                                if rc.ui.is_item_hovered() {
+                                    hover_text = Some(promotion.info());
+                                }
+                            }
+                        }
+                        if unit.health() < unit.max_health() {
+                            rc.ui.text(format!("Heals {} per turn when resting", self.world.unit_healing(unit)));
+                        }
+                        if unit.maintenance() > 0.0.into() {
+                            rc.ui.text(format!("Upkeep: {} gold", unit.maintenance()));
+                        }
                         if let Some((current, initial)) = unit.charges() {
                             rc.ui.text(format!("Charges: {}/{}", current, initial));
                         }
@@ -543,13 +674,53 @@ impl InGameState {
                         rc.ui.spacing();
                         rc.ui.spacing();
 
-                        let mut sleeping = unit.sleeping();
-                        if rc.ui.checkbox(im_str!("Sleeping"), &mut sleeping) {
-                            self.connection.send_message(MessageToServer::Action(GameActionType::SetSleeping { unit_id: *unit_id, sleeping }));
+                        if let Some(order) = unit.order() {
+                            rc.ui.text(format!("Order: {}", order));
+                            if rc.ui.button(im_str!("Cancel order"), sidebar_button_size) {
+                                let action = GameActionType::SetUnitOrder { unit_id: *unit_id, order: None };
+                                self.connection.send_message(MessageToServer::Action(action));
+                            }
+                        } else {
+                            for &order in &[UnitOrder::Sleep, UnitOrder::Fortify, UnitOrder::Heal, UnitOrder::Skip] {
+                                if unit.can_take_order(order) && rc.ui.button(&ImString::new(order.to_string()), sidebar_button_size) {
+                                    let action = GameActionType::SetUnitOrder { unit_id: *unit_id, order: Some(order) };
+                                    self.connection.send_message(MessageToServer::Action(action));
+                                }
+                            }
+                        }
+                        if unit.fortification_bonus() > 0.0 {
+                            rc.ui.text(format!("Fortified: +{:.0}% defence", unit.fortification_bonus() * 100.0));
+                        }
+
+                        if let Some(destination) = unit.move_path().last() {
+                            rc.ui.text(format!("Moving to {}", destination));
+                            if rc.ui.button(im_str!("Cancel move"), sidebar_button_size) {
+                                let action = GameActionType::SetMoveOrder { unit_id: *unit_id, destination: None };
+                                self.connection.send_message(MessageToServer::Action(action));
+                            }
+                        }
+
+                        if let Some(order) = unit.improvement_order() {
+                            let improvement_type = self.world.improvement_types().get(order.improvement_type_id);
+                            rc.ui.text(format!("Building {} ({} turns)", improvement_type.name, order.remaining_turns));
                         }
 
                         rc.ui.spacing();
 
+                        let you_civ_id = self.world.player(self.player_id).unwrap().civilization_id();
+                        if unit.can_be_promoted() && unit.owner() == you_civ_id {
+                            for promotion in self.world.promotions().all().filter(|promotion| unit.can_take_promotion(promotion)) {
+                                let label = format!("Promote: {}", promotion.name);
+                                if rc.ui.button(&ImString::new(label), sidebar_button_size) {
+                                    let action = GameActionType::PromoteUnit { unit_id: *unit_id, promotion_id: promotion.id };
+                                    self.connection.send_message(MessageToServer::Action(action));
+                                }
+                                if rc.ui.is_item_hovered() {
+                                    hover_text = Some(promotion.info());
+                                }
+                            }
+                        }
+
                         for ability in unit.abilities() {
                             match ability {
                                 UnitAbility::Settle => {
@@ -567,6 +738,51 @@ impl InGameState {
                                         self.connection.send_message(MessageToServer::Action(action));
                                     }
                                 }
+                                UnitAbility::EstablishTradeRoute => {
+                                    for destination in self.world.trade_route_destinations(unit) {
+                                        let city = self.world.city(destination).unwrap();
+                                        let label = format!("Trade with {}", city.name());
+                                        if rc.ui.button(&ImString::new(label), sidebar_button_size) {
+                                            let action = GameActionType::EstablishTradeRoute { unit_id: *unit_id, destination };
+                                            self.connection.send_message(MessageToServer::Action(action));
+                                        }
+                                    }
+                                }
+                                UnitAbility::BuildImprovements => {
+                                    for improvement_type in self.world.improvement_types().all() {
+                                        if !self.world.can_build_improvement(unit, improvement_type.id) {
+                                            continue;
+                                        }
+
+                                        let label = format!("Build {} ({} turns)", improvement_type.name, improvement_type.build_turns);
+                                        if rc.ui.button(&ImString::new(label), sidebar_button_size) {
+                                            let action = GameActionType::BuildImprovement { unit_id: *unit_id, improvement_type_id: improvement_type.id };
+                                            self.connection.send_message(MessageToServer::Action(action));
+                                        }
+                                        if rc.ui.is_item_hovered() {
+                                            hover_text = Some(improvement_type.info());
+                                        }
+                                    }
+                                }
+                            }
+                        }
+
+                        if unit.owner() == you_civ_id {
+                            if let Some(target) = self.world.unit_upgrade_target(unit) {
+                                let label = format!("Upgrade to {} ({} gold)", target.name, self.world.unit_upgrade_cost(unit, target));
+                                if rc.ui.button(&ImString::new(label), sidebar_button_size) {
+                                    let action = GameActionType::UpgradeUnit { unit_id: *unit_id };
+                                    self.connection.send_message(MessageToServer::Action(action));
+                                }
+                            }
+                        }
+
+                        if let Some(city_state_id) = self.world.city_state_gift_target(unit) {
+                            let city_state_name = self.world.civilization(city_state_id).unwrap().player_name();
+                            let label = format!("Gift to {}", city_state_name);
+                            if rc.ui.button(&ImString::new(label), sidebar_button_size) {
+                                let action = GameActionType::GiftUnit { unit_id: *unit_id };
+                                self.connection.send_message(MessageToServer::Action(action));
                             }
                         }
                     }
@@ -582,8 +798,24 @@ impl InGameState {
 
                         let owner_name = self.world.civilization(city.owner()).unwrap().player_name();
                         rc.ui.text(format!("Owner: {}", owner_name));
+                        if city.original_owner() != city.owner() {
+                            let founder_name = self.world.civilization(city.original_owner()).unwrap().player_name();
+                            rc.ui.text(format!("Founded by: {}", founder_name));
+                        }
                         rc.ui.text(format!("City at {}", city.position()));
 
+                        let you_civ_id = self.world.player(self.player_id).unwrap().civilization_id();
+                        if city.owner() == you_civ_id && city.can_be_razed() {
+                            let mut razing = city.razing();
+                            if rc.ui.checkbox(im_str!("Raze city"), &mut razing) {
+                                let action = GameActionType::SetRazing { city_id: *city_id, razing };
+                                self.connection.send_message(MessageToServer::Action(action));
+                            }
+                        }
+                        if city.razing() {
+                            rc.ui.text(format!("Razed in {} turns", city.population()));
+                        }
+
                         if city_name_changed {
                             let action = GameActionType::RenameCity { city_id: *city_id, name: city_name_buf.to_string() };
                             self.connection.send_message(MessageToServer::Action(action));
@@ -593,15 +825,25 @@ impl InGameState {
                         rc.ui.separator();
                         rc.ui.spacing();
 
-                        rc.ui.text(format!(
-                            "Growth: {}/{:.2} ({} turns remaining)",
-                            city.accumulated_food(),
-                            city.required_food_for_population_increase(),
-                            city.turns_until_population_increase(),
-                        ));
+                        if self.world.civilization_happiness(city.owner()) < 0 {
+                            rc.ui.text(format!(
+                                "Growth: {}/{:.2} (halted by unhappiness)",
+                                city.accumulated_food(),
+                                city.required_food_for_population_increase(),
+                            ));
+                        } else {
+                            rc.ui.text(format!(
+                                "Growth: {}/{:.2} ({} turns remaining)",
+                                city.accumulated_food(),
+                                city.required_food_for_population_increase(),
+                                city.turns_until_population_increase(),
+                            ));
+                        }
                         if city.next_tile_to_expand_to(&self.world.map).is_some() {
                             rc.ui.text(format!(
-                                "{} turns until territory expansion",
+                                "Culture: {}/{} ({} turns until territory expansion)",
+                                city.accumulated_culture(),
+                                city.required_culture_for_territory_growth(),
                                 city.turns_until_territory_growth(),
                             ));
                         } else {
@@ -619,6 +861,20 @@ impl InGameState {
                         rc.ui.text(format!("Food: {}", yields.food));
                         rc.ui.text(format!("Production: {}", yields.production));
                         rc.ui.text(format!("Science: {}", yields.science));
+                        rc.ui.text(format!("Gold: {}", yields.gold));
+                        rc.ui.text(format!("Culture: {}", yields.culture));
+
+                        let routes: Vec<_> = self.world.trade_routes().filter(|route| route.involves(*city_id)).collect();
+                        if !routes.is_empty() {
+                            rc.ui.spacing();
+                            rc.ui.text("Trade routes:");
+                            for route in routes {
+                                let (direction, other) = if route.origin == *city_id { ("To", route.destination) } else { ("From", route.origin) };
+                                rc.ui.text(format!("{} {}", direction, self.world.city(other).unwrap().name()));
+                            }
+                            let trade_yields = self.world.city_trade_yields(*city_id);
+                            rc.ui.text(format!("Trade: +{} Gold, +{} Science", trade_yields.gold, trade_yields.science));
+                        }
 
                         rc.ui.spacing();
                         rc.ui.separator();
@@ -632,46 +888,96 @@ impl InGameState {
                                 producing_unit.production_cost(),
                                 production_remaining.div_to_get_turn_count(yields.production),
                             ));
+                            if self.world.production_stalled(*city_id) {
+                                let resources: Vec<_> = producing_unit.required_resources()
+                                    .iter()
+                                    .map(|(resource, amount)| format!("{} {}", amount, resource))
+                                    .collect();
+                                rc.ui.text(format!("Waiting for {}", resources.join(", ")));
+                            }
+
+                            let purchase_cost = city.purchase_cost().unwrap();
+                            if city.owner() == you_civ_id && self.world.civilization(you_civ_id).unwrap().gold() >= purchase_cost {
+                                let label = format!("Buy for {} gold", purchase_cost);
+                                if rc.ui.button(&ImString::new(label), sidebar_button_size) {
+                                    let action = GameActionType::PurchaseItem { city_id: *city_id, item: producing_unit.id() };
+                                    self.connection.send_message(MessageToServer::Action(action));
+                                }
+                            }
                         } else {
                             rc.ui.text("Production: None");
                         }
+
+                        for (index, item) in city.production_queue().iter().enumerate() {
+                            let id_token = rc.ui.push_id(index as i32);
+                            rc.ui.text(format!("{}. {}", index + 1, item.name()));
+                            if index > 0 {
+                                rc.ui.same_line(0.0);
+                                if rc.ui.small_button(im_str!("Up")) {
+                                    let action = GameActionType::MoveQueuedProduction { city_id: *city_id, from: index, to: index - 1 };
+                                    self.connection.send_message(MessageToServer::Action(action));
+                                }
+                            }
+                            rc.ui.same_line(0.0);
+                            if rc.ui.small_button(im_str!("Remove")) {
+                                let action = GameActionType::RemoveQueuedProduction { city_id: *city_id, index };
+                                self.connection.send_message(MessageToServer::Action(action));
+                            }
+                            id_token.pop(&rc.ui);
+                        }
                         rc.ui.spacing();
                         rc.ui.separator();
                         rc.ui.spacing();
 
                         rc.ui.text(im_str!("Production List"));
+                        rc.ui.text(im_str!("Shift-click to add to the queue"));
+                        let enqueue = rc.ui.io().key_shift;
                         for unit_template_id in city.producible_units() {
                             let unit_template = self.world.unit_templates().get(*unit_template_id);
+                            let stored = city.stored_production(&ProducingItemId::Unit(unit_template.id));
                             let label = format!(
                                 "{}: {} ({} turns)",
                                 unit_template.name,
                                 unit_template.production_cost,
-                                unit_template.turn_cost(yields.production),
+                                unit_template.turn_cost(yields.production, stored + city.overflow_production()),
                             );
 
                             let chose = rc.ui.button(&ImString::new(label), sidebar_button_size);
                             if chose {
-                                let action = GameActionType::SetProducing {
-                                    city_id: *city_id,
-                                    producing: Some(ProducingItemId::Unit(unit_template.clone())),
+                                let item = ProducingItemId::Unit(unit_template.id);
+                                let action = if enqueue {
+                                    GameActionType::EnqueueProduction { city_id: *city_id, item }
+                                } else {
+                                    GameActionType::SetProducing { city_id: *city_id, producing: Some(item) }
                                 };
                                 self.connection.send_message(MessageToServer::Action(action));
                             }
+
+                            if rc.ui.is_item_hovered() && !unit_template.required_resources.is_empty() {
+                                let requirements: Vec<_> = unit_template.required_resources
+                                    .iter()
+                                    .map(|(resource, amount)| format!("Requires: {} {}", amount, resource))
+                                    .collect();
+                                hover_text = Some(requirements.join("\n"));
+                            }
                         }
 
                         for building_type in city.producible_buildings() {
+                            let stored = city.stored_production(&ProducingItemId::Building(building_type.id));
                             let label = format!(
                                 "{}: {} ({} turns)",
                                 building_type.name,
                                 building_type.production_cost,
-                                building_type.turn_cost(yields.production),
+                                building_type.turn_cost(yields.production, stored + city.overflow_production()),
                             );
 
                             let chose = rc.ui.button(&ImString::new(label), sidebar_button_size);
                             if chose {
-                                let action = GameActionType::SetProducing {
-                                    city_id: *city_id,
-                                    producing: Some(ProducingItemId::Building(building_type.id)),
+                                let item = ProducingItemId::Building(building_type.id);
+                                let action = if enqueue {
+                                    GameActionType::EnqueueProduction { city_id: *city_id, item }
+                                } else {
+                                    GameActionType::SetProducing { city_id: *city_id, producing: Some(item) }
                                 };
                                 self.connection.send_message(MessageToServer::Action(action));
                             }
@@ -802,7 +1108,7 @@ impl InGameState {
                     }
 
                     if rc.ui.is_item_hovered() {
-                        self.draw_mouse_overlay_ui(tech.info(self.world.unit_templates()), ctx, rc);
+                        self.draw_mouse_overlay_ui(tech.info(self.world.unit_templates(), self.world.improvement_types()), ctx, rc);
                     }
 
                     for dependency_id in tech.dependencies() {
@@ -817,6 +1123,145 @@ impl InGameState {
         }
     }
 
+    pub(super) fn draw_diplomacy_ui(&mut self, ctx: &mut Context, rc: &ImGuiRenderContext) {
+        use imgui::*;
+
+        let Rect { w: screen_width, h: screen_height, .. } = graphics::screen_coordinates(ctx);
+        let you_civ_id = self.world.player(self.player_id).unwrap().civilization_id();
+
+        let width = rc.ui.current_font_size() * 24.0;
+        let button_size = [width / 2.0, 0.0];
+        let mut opened = true;
+        let mut actions = vec![];
+
+        imgui::Window::new(im_str!("Diplomacy"))
+            .size([width, screen_height / 2.0], imgui::Condition::Always)
+            .position([(screen_width - width) / 2.0, screen_height / 4.0], imgui::Condition::Always)
+            .collapsible(false)
+            .movable(false)
+            .resizable(false)
+            .opened(&mut opened)
+            .build(&rc.ui, || {
+                for civilization in self.world.civilizations() {
+                    let civilization_id = civilization.id();
+                    if civilization_id == you_civ_id || civilization.eliminated() || civilization.is_barbarian() {
+                        continue;
+                    }
+
+                    let id_token = rc.ui.push_id(format!("{:?}", civilization_id).as_str());
+                    let diplomacy = self.world.diplomacy();
+                    let state = diplomacy.state(you_civ_id, civilization_id);
+                    rc.ui.text(format!("{}: {}", civilization.player_name(), state));
+
+                    if let Some(city_state_type) = civilization.city_state_type() {
+                        let influence = civilization.influence(you_civ_id);
+                        let relationship = self.world.city_state_relationship(civilization_id, you_civ_id);
+                        rc.ui.text(format!("{} city-state", city_state_type));
+                        rc.ui.text(format!("Influence: {} ({})", influence, relationship));
+
+                        let gift_gold: YieldValue = CITY_STATE_GOLD_GIFT.into();
+                        let you_gold = self.world.civilization(you_civ_id).unwrap().gold();
+                        let can_gift = state != DiplomaticState::War && you_gold >= gift_gold;
+                        if can_gift && rc.ui.button(&ImString::new(format!("Gift {} gold", gift_gold)), button_size) {
+                            actions.push(GameActionType::GiftGold { civilization_id });
+                        }
+                    } else if let Some(proposed) = diplomacy.proposal(civilization_id, you_civ_id) {
+                        rc.ui.text(format!("Proposes {}", proposed));
+                        if rc.ui.small_button(im_str!("Accept")) {
+                            actions.push(GameActionType::RespondToProposal { civilization_id, accept: true });
+                        }
+                        rc.ui.same_line(0.0);
+                        if rc.ui.small_button(im_str!("Reject")) {
+                            actions.push(GameActionType::RespondToProposal { civilization_id, accept: false });
+                        }
+                    }
+
+                    if let Some(proposed) = diplomacy.proposal(you_civ_id, civilization_id) {
+                        rc.ui.text(format!("Waiting for a reply to {}", proposed));
+                    } else {
+                        for &proposal in &[DiplomaticState::Peace, DiplomaticState::OpenBorders, DiplomaticState::Alliance] {
+                            if proposal.can_propose_from(state) && rc.ui.button(&ImString::new(format!("Propose {}", proposal)), button_size) {
+                                actions.push(GameActionType::ProposeDiplomaticState { civilization_id, state: proposal });
+                            }
+                        }
+                    }
+
+                    if state != DiplomaticState::War && rc.ui.button(im_str!("Declare war"), button_size) {
+                        actions.push(GameActionType::DeclareWar { civilization_id });
+                    }
+
+                    id_token.pop(&rc.ui);
+                    rc.ui.separator();
+                }
+            });
+
+        for action in actions {
+            self.connection.send_message(MessageToServer::Action(action));
+        }
+
+        if !opened {
+            self.display_diplomacy = false;
+        }
+    }
+
+    pub(super) fn draw_statistics_ui(&mut self, ctx: &mut Context, rc: &ImGuiRenderContext) {
+        use imgui::*;
+
+        let Rect { w: screen_width, h: screen_height, .. } = graphics::screen_coordinates(ctx);
+
+        let width = rc.ui.current_font_size() * 30.0;
+        let graph_size = [width - rc.ui.clone_style().window_padding[0] * 2.0, rc.ui.current_font_size() * 4.0];
+        let mut opened = true;
+        let mut exporting = false;
+        let mut displayed_statistic = self.displayed_statistic;
+
+        imgui::Window::new(im_str!("Statistics"))
+            .size([width, screen_height * 0.75], imgui::Condition::Always)
+            .position([(screen_width - width) / 2.0, screen_height / 8.0], imgui::Condition::Always)
+            .collapsible(false)
+            .movable(false)
+            .resizable(false)
+            .opened(&mut opened)
+            .build(&rc.ui, || {
+                for (i, &statistic) in Statistic::ALL.iter().enumerate() {
+                    if i % 5 != 0 {
+                        rc.ui.same_line(0.0);
+                    }
+                    rc.ui.radio_button(&ImString::new(statistic.to_string()), &mut displayed_statistic, statistic);
+                }
+
+                rc.ui.spacing();
+                rc.ui.separator();
+                rc.ui.spacing();
+
+                for player in self.world.players() {
+                    let civilization = self.world.civilization(player.civilization_id()).unwrap();
+                    let values = self.world.statistics().series(civilization.id(), displayed_statistic);
+                    let latest = values.last().copied().unwrap_or(0.0);
+
+                    rc.ui.text(format!("{}: {}", civilization.player_name(), latest));
+                    let label = ImString::new(format!("##{:?}", civilization.id()));
+                    rc.ui.plot_lines(&label, &values)
+                        .scale_min(0.0)
+                        .graph_size(graph_size)
+                        .build();
+                }
+
+                rc.ui.spacing();
+                exporting = rc.ui.button(im_str!("Export CSV"), [graph_size[0], 0.0]);
+            });
+
+        self.displayed_statistic = displayed_statistic;
+
+        if exporting {
+            self.export_statistics();
+        }
+
+        if !opened {
+            self.display_statistics = false;
+        }
+    }
+
     const TECH_TREE_LINE_COLOR: [f32; 3] = [1.0, 1.0, 1.0];
     const TECH_TREE_SCALE: f32 = 1000.0;
     const TECH_TREE_PADDING: [f32; 2] = [50.0, 50.0];
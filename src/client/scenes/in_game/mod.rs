@@ -22,12 +22,17 @@ use crate::common::{
     MessageToServer,
     PlayerId,
     Citizen,
+    City,
+    TilePosition,
+    YieldValue,
+    Statistic,
 };
 
 use crate::client::InputEvent;
 use crate::client::SharedData;
 use crate::client::constants::*;
 use super::crash::CrashState;
+use super::game_over::GameOverState;
 use crate::client::drag::Drag;
 use crate::client::hitbox::{Hitbox, HitboxKey, get_hovered_object};
 use crate::client::selected_object::SelectedObject;
@@ -55,6 +60,11 @@ pub struct InGameState {
     quitting: bool,
     crash: Option<String>,
     display_tech_tree: bool,
+    display_diplomacy: bool,
+    display_statistics: bool,
+    displayed_statistic: Statistic,
+    // The results are shown once, after which the map can still be looked at.
+    displayed_game_over: bool,
 }
 
 impl InGameState {
@@ -104,6 +114,10 @@ impl InGameState {
             quitting: false,
             crash: None,
             display_tech_tree: false,
+            display_diplomacy: false,
+            display_statistics: false,
+            displayed_statistic: Statistic::Score,
+            displayed_game_over: false,
         };
         Ok(s)
     }
@@ -112,6 +126,22 @@ impl InGameState {
         self.connection.send_message(MessageToServer::Action(action));
     }
 
+    fn export_map(&self) {
+        let path = format!("map_turn_{}.ron", self.world.turn());
+        match std::fs::write(&path, self.world.to_map_file().to_ron()) {
+            Ok(()) => println!("Exported map to {}", path),
+            Err(error) => println!("Failed to export map to {}: {}", path, error),
+        }
+    }
+
+    fn export_statistics(&self) {
+        let path = format!("statistics_turn_{}.csv", self.world.turn());
+        match std::fs::write(&path, self.world.statistics_csv()) {
+            Ok(()) => println!("Exported statistics to {}", path),
+            Err(error) => println!("Failed to export statistics to {}: {}", path, error),
+        }
+    }
+
     fn on_quit(&mut self) {
         self.connection.send_message(MessageToServer::Quit);
     }
@@ -147,6 +177,13 @@ impl InGameState {
                     Hitbox::unit(position, template.unit_type),
                 );
             }
+            GameEventType::DestroyCity { city_id } => {
+                if let Some(SelectedObject::City(selected_city_id, _)) = self.selected {
+                    if selected_city_id == city_id {
+                        self.selected = None;
+                    }
+                }
+            }
             GameEventType::Crash { ref message } => {
                 self.crash = Some(message.clone());
             }
@@ -157,6 +194,23 @@ impl InGameState {
     fn can_control_unit(&self, unit: &crate::common::Unit) -> bool {
         self.world.player(self.player_id).unwrap().civilization_id() == unit.owner()
     }
+
+    fn can_control_city(&self, city: &City) -> bool {
+        self.world.player(self.player_id).unwrap().civilization_id() == city.owner()
+    }
+
+    fn purchasable_tiles(&self, city: &City) -> Vec<(TilePosition, YieldValue)> {
+        if !self.can_control_city(city) {
+            return vec![];
+        }
+
+        let map = &self.world.map;
+        city.position()
+            .neighbors_at_distance(map.width(), map.height(), City::MAX_TERRITORY_DISTANCE, false)
+            .keys()
+            .filter_map(|pos| city.tile_purchase_cost(*pos, map).map(|cost| (*pos, cost)))
+            .collect()
+    }
 }
 
 impl Scene<SharedData, InputEvent> for InGameState {
@@ -177,6 +231,11 @@ impl Scene<SharedData, InputEvent> for InGameState {
             }
         }
 
+        if self.world.game_over().is_some() && !self.displayed_game_over {
+            self.displayed_game_over = true;
+            return SceneSwitch::Push(Box::new(GameOverState::new(self.world.clone(), self.player_id)));
+        }
+
         // TODO sleep enough to limit to 60 UPS
         // Possibly less? Could do with like 20
         std::thread::yield_now();
@@ -194,6 +253,7 @@ impl Scene<SharedData, InputEvent> for InGameState {
             self.draw_tiles(ctx);
             self.draw_rivers(ctx);
             self.draw_cities_borders(ctx);
+            self.draw_barbarian_camps(ctx);
             self.draw_units(ctx);
             self.draw_selected_highlight(ctx);
             self.draw_tiles_yields(ctx);
@@ -211,6 +271,12 @@ impl Scene<SharedData, InputEvent> for InGameState {
             if self.display_tech_tree {
                 self.draw_tech_tree_ui(ctx, &rc);
             }
+            if self.display_diplomacy {
+                self.draw_diplomacy_ui(ctx, &rc);
+            }
+            if self.display_statistics {
+                self.draw_statistics_ui(ctx, &rc);
+            }
 
             rc.render(ctx);
         }
@@ -247,6 +313,9 @@ impl Scene<SharedData, InputEvent> for InGameState {
                     for pos in tiles {
                         hitboxes.insert(HitboxKey::Citizen(pos), Hitbox::citizen(pos));
                     }
+                    for (pos, _) in self.purchasable_tiles(city) {
+                        hitboxes.insert(HitboxKey::PurchasableTile(pos), Hitbox::purchasable_tile(pos));
+                    }
                 }
                 let hovered = get_hovered_object(x, y, self.zoom, &self.offset, &hitboxes);
 
@@ -282,15 +351,32 @@ impl Scene<SharedData, InputEvent> for InGameState {
                                     unreachable!();
                                 }
                             }
+                            HitboxKey::PurchasableTile(position) => {
+                                if let Some(SelectedObject::City(city_id, _)) = self.selected {
+                                    self.send_action(GameActionType::BuyTile { city_id, position });
+                                } else {
+                                    unreachable!();
+                                }
+                            }
                         }
                     };
                 } else if let MouseButton::Right = button {
                     if let Some(SelectedObject::Unit(unit_id)) = self.selected {
-                        if let Some(HitboxKey::Tile(pos)) = hovered {
-                            let sp = self.world.map.shortest_path(self.world.unit(unit_id).unwrap().position(),pos).unwrap();
-                            println!("{:?}", sp);
-
-                            let action = GameActionType::MoveUnit { unit_id, position: pos };
+                        let target = match hovered {
+                            Some(HitboxKey::Tile(pos)) => Some(pos),
+                            Some(HitboxKey::Unit(target_unit_id)) => self.world.unit(target_unit_id).map(|unit| unit.position()),
+                            _ => None,
+                        };
+
+                        if let Some(pos) = target {
+                            let unit = self.world.unit(unit_id).unwrap();
+                            let action = if !self.world.foreign_units_at(pos, unit.owner()).is_empty() {
+                                GameActionType::Attack { unit_id, position: pos }
+                            } else if self.world.reachable_tiles(unit).contains_key(&pos) {
+                                GameActionType::MoveUnit { unit_id, position: pos }
+                            } else {
+                                GameActionType::SetMoveOrder { unit_id, destination: Some(pos) }
+                            };
                             self.send_action(action);
                         }
                     }
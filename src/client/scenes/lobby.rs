@@ -5,13 +5,14 @@ use std::net::TcpStream;
 use ggez::graphics;
 use ggez::graphics::Rect;
 use ggez::event::KeyCode;
+use imgui::ImString;
 use crate::client::scene::{Scene, SceneSwitch};
 
 use crate::client::InputEvent;
 use crate::client::SharedData;
 use crate::client::imgui_wrapper::ImGuiFonts;
 use crate::client::scenes::in_game::InGameState;
-use crate::common::{DEFAULT_SERVER, Connection, LobbyInfo, MessageToClient, MessageToServer};
+use crate::common::{DEFAULT_SERVER, Connection, GameSettings, LobbyInfo, MapFile, MessageToClient, MessageToServer, VictoryConditions};
 use crate::server;
 
 pub struct LobbyState {
@@ -21,6 +22,10 @@ pub struct LobbyState {
     connection: Option<Connection<MessageToServer, MessageToClient>>,
     starting_game: bool,
     lobby_info: Option<LobbyInfo>,
+    // Path to a map file to play on. A map is generated if this is empty.
+    map_path: ImString,
+    map_error: Option<String>,
+    victory_conditions: VictoryConditions,
 }
 
 fn start_server() {
@@ -52,8 +57,26 @@ impl LobbyState {
             hosting,
             connection: Some(connection),
             lobby_info: None,
+            map_path: ImString::new(""),
+            map_error: None,
+            victory_conditions: VictoryConditions::default(),
         }
     }
+
+    fn game_settings(&self) -> Result<GameSettings, String> {
+        let map_path = self.map_path.to_str().trim();
+        let victory_conditions = self.victory_conditions.clone();
+        if map_path.is_empty() {
+            return Ok(GameSettings { map: None, victory_conditions });
+        }
+
+        let contents = std::fs::read_to_string(map_path).map_err(|error| format!("Could not read {}: {}", map_path, error))?;
+        let map_file = MapFile::from_ron(&contents)?;
+        let num_players = self.lobby_info.as_ref().map(|lobby_info| lobby_info.players.len()).unwrap_or(1);
+        map_file.validate(num_players)?;
+
+        Ok(GameSettings { map: Some(map_file), victory_conditions })
+    }
 }
 
 impl Scene<SharedData, InputEvent> for LobbyState {
@@ -65,7 +88,15 @@ impl Scene<SharedData, InputEvent> for LobbyState {
 
         if self.starting_game {
             self.starting_game = false;
-            self.connection.as_mut().unwrap().send_message(MessageToServer::Start);
+            match self.game_settings() {
+                Ok(settings) => {
+                    self.map_error = None;
+                    self.connection.as_mut().unwrap().send_message(MessageToServer::Start(settings));
+                }
+                Err(error) => {
+                    self.map_error = Some(error);
+                }
+            }
         }
 
         if let Some(connection) = &mut self.connection {
@@ -77,6 +108,9 @@ impl Scene<SharedData, InputEvent> for LobbyState {
                     MessageToClient::InitializeWorld { world, player_id } => {
                         return SceneSwitch::Push(Box::new(InGameState::new(ctx, world, player_id, self.connection.take().unwrap()).unwrap()));
                     }
+                    MessageToClient::StartFailed(error) => {
+                        self.map_error = Some(error);
+                    }
                     MessageToClient::Kick => {
                         return SceneSwitch::Pop;
                     }
@@ -99,7 +133,7 @@ impl Scene<SharedData, InputEvent> for LobbyState {
             use imgui::*;
 
             let window_width = ui.current_font_size() * 22.0;
-            let window_height = ui.current_font_size() * 16.0;
+            let window_height = ui.current_font_size() * 22.0;
 
             let full_button_size: [f32; 2] = [window_width - ui.clone_style().window_padding[0] * 2.0, ui.current_font_size() * 2.0];
 
@@ -112,6 +146,31 @@ impl Scene<SharedData, InputEvent> for LobbyState {
                     if self.hosting {
                         ui.text(format!("Hosting as: {}", DEFAULT_SERVER));
                         ui.spacing();
+                        let map_path_changed = imgui::InputText::new(ui, im_str!("Map file"), &mut self.map_path)
+                            .resize_buffer(true)
+                            .build();
+                        if map_path_changed {
+                            self.map_error = None;
+                        }
+                        if let Some(map_error) = &self.map_error {
+                            ui.text_wrapped(&ImString::new(map_error));
+                        }
+                        ui.spacing();
+
+                        let victory_conditions = &mut self.victory_conditions;
+                        ui.checkbox(im_str!("Domination victory"), &mut victory_conditions.domination);
+                        ui.checkbox(im_str!("Science victory"), &mut victory_conditions.science);
+                        let mut has_turn_limit = victory_conditions.turn_limit.is_some();
+                        if ui.checkbox(im_str!("Turn limit"), &mut has_turn_limit) {
+                            victory_conditions.turn_limit = if has_turn_limit { Some(VictoryConditions::DEFAULT_TURN_LIMIT) } else { None };
+                        }
+                        if let Some(turn_limit) = &mut victory_conditions.turn_limit {
+                            let mut value = *turn_limit as i32;
+                            if ui.input_int(im_str!("Last turn"), &mut value).build() {
+                                *turn_limit = value.max(1).min(u16::MAX as i32) as u16;
+                            }
+                        }
+                        ui.spacing();
                         self.starting_game = ui.button(im_str!("Start Game"), full_button_size);
                     } else {
                         ui.text(format!("Connected to: {}", self.connection.as_ref().unwrap().peer_addr()));
@@ -3,4 +3,5 @@ pub mod in_game;
 pub mod main_menu;
 pub mod lobby;
 pub mod input_server_addr;
-pub mod crash;
\ No newline at end of file
+pub mod crash;
+pub mod game_over;
\ No newline at end of file
@@ -7,8 +7,34 @@ pub enum GameActionType {
     RenameCity { city_id: CityId, name: String },
     SetReady(bool),
     SetProducing { city_id: CityId, producing: Option<ProducingItemId> },
-    SetSleeping { unit_id: UnitId, sleeping: bool },
+    EnqueueProduction { city_id: CityId, item: ProducingItemId },
+    MoveQueuedProduction { city_id: CityId, from: usize, to: usize },
+    RemoveQueuedProduction { city_id: CityId, index: usize },
+    // None cancels the unit's standing order.
+    SetUnitOrder { unit_id: UnitId, order: Option<UnitOrder> },
+    PromoteUnit { unit_id: UnitId, promotion_id: PromotionId },
+    // The unit must be in its owner's territory.
+    UpgradeUnit { unit_id: UnitId },
     SetCitizenLocked { city_id: CityId, position: TilePosition, locked: bool },
     Harvest { unit_id: UnitId },
+    BuildImprovement { unit_id: UnitId, improvement_type_id: ImprovementTypeId },
+    // The unit must be in one of its owner's cities, which becomes the origin.
+    EstablishTradeRoute { unit_id: UnitId, destination: CityId },
     SetResearch { tech_id: TechId },
+    Attack { unit_id: UnitId, position: TilePosition },
+    SetRazing { city_id: CityId, razing: bool },
+    // Buys the item the city is currently producing.
+    PurchaseItem { city_id: CityId, item: ProducingItemId },
+    BuyTile { city_id: CityId, position: TilePosition },
+    DeclareWar { civilization_id: CivilizationId },
+    // Peace, open borders or an alliance, which the other civilization has to accept.
+    ProposeDiplomaticState { civilization_id: CivilizationId, state: DiplomaticState },
+    // Accepts or rejects the proposal made by the given civilization.
+    RespondToProposal { civilization_id: CivilizationId, accept: bool },
+    // Gifts a fixed amount of gold to a city-state for influence.
+    GiftGold { civilization_id: CivilizationId },
+    // The unit must be in the territory of the city-state it is gifted to.
+    GiftUnit { unit_id: UnitId },
+    // None cancels the current move order.
+    SetMoveOrder { unit_id: UnitId, destination: Option<TilePosition> },
 }
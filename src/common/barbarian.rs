@@ -0,0 +1,38 @@
+use serde::{Serialize, Deserialize};
+
+use crate::common::*;
+
+// Barbarian camps don't appear within this distance of player units and cities.
+pub const BARBARIAN_SIGHT_RANGE: MapUnit = 3;
+// Raiders only go after cities within this distance.
+pub const BARBARIAN_RAID_RANGE: MapUnit = 8;
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct BarbarianCamp {
+    pub position: TilePosition,
+    pub founded_turn: u16,
+}
+
+impl BarbarianCamp {
+    // Camps start appearing once players have had some time to settle.
+    pub const FIRST_SPAWN_TURN: u16 = 10;
+    pub const SPAWN_INTERVAL: u16 = 5;
+    pub const MAX_RAIDERS: usize = 2;
+    pub const RAIDER_SPAWN_INTERVAL: u16 = 6;
+    pub const CLEAR_REWARD: f32 = 25.0;
+    pub const CLEAR_EXPERIENCE: u16 = 5;
+    pub const RAID_GOLD: f32 = 5.0;
+
+    pub fn new(position: TilePosition, founded_turn: u16) -> Self {
+        Self { position, founded_turn }
+    }
+
+    pub fn spawns_raider_on(&self, turn: u16) -> bool {
+        turn > self.founded_turn && (turn - self.founded_turn) % Self::RAIDER_SPAWN_INTERVAL == 0
+    }
+
+    // Larger maps support more camps at once.
+    pub fn max_camps(map: &GameMap) -> usize {
+        (map.width() as usize * map.height() as usize) / 200 + 1
+    }
+}
@@ -11,11 +11,15 @@ pub struct BuildingType {
     pub name: String,
     pub effects: Vec<CityEffect>,
     pub production_cost: YieldValue,
+    // Gold paid by the owner every turn.
+    pub maintenance: YieldValue,
+    // Strategic resources consumed when the building is completed.
+    pub required_resources: BTreeMap<ResourceType, u16>,
 }
 
 impl BuildingType {
-    pub fn turn_cost(&self, production: YieldValue) -> usize {
-        self.production_cost.div_to_get_turn_count(production)
+    pub fn turn_cost(&self, production: YieldValue, progress: YieldValue) -> usize {
+        (self.production_cost - progress).div_to_get_turn_count(production)
     }
 
     pub fn effect_info(&self) -> String {
@@ -24,6 +28,12 @@ impl BuildingType {
         for effect in &self.effects {
             info += &format!("{}", effect);
         }
+        if self.maintenance > 0.0.into() {
+            info += &format!("Upkeep: {} Gold\n", self.maintenance);
+        }
+        for (resource, amount) in &self.required_resources {
+            info += &format!("Requires: {} {}\n", amount, resource);
+        }
         info
     }
 }
@@ -62,6 +72,8 @@ impl BuildingTypes {
                 id: s.generator.next(),
                 name: "Granary".into(),
                 production_cost: 25.0.into(),
+                maintenance: 1.0.into(),
+                required_resources: BTreeMap::new(),
                 effects: vec![
                     CityEffect::MulYield(YieldMultiplier { multiplier: 1.2.into(), yield_type: YieldType::Food }),
                 ],
@@ -70,13 +82,67 @@ impl BuildingTypes {
                 id: s.generator.next(),
                 name: "Stable".into(),
                 production_cost: 25.0.into(),
+                maintenance: 1.0.into(),
+                required_resources: BTreeMap::new(),
                 effects: vec![
                     CityEffect::AddTileYield {
                         yield_: Yield { value: 1.0.into(), yield_type: YieldType::Production },
                         matcher: TileMatcher::HasResource(ResourceType::Horses),
                     },
                 ],
-            }
+            },
+            BuildingType {
+                id: s.generator.next(),
+                name: "Market".into(),
+                production_cost: 30.0.into(),
+                maintenance: 0.0.into(),
+                required_resources: BTreeMap::new(),
+                effects: vec![
+                    CityEffect::AddYield(Yield { value: 2.0.into(), yield_type: YieldType::Gold }),
+                    CityEffect::MulYield(YieldMultiplier { multiplier: 1.25.into(), yield_type: YieldType::Gold }),
+                ],
+            },
+            BuildingType {
+                id: s.generator.next(),
+                name: "Arena".into(),
+                production_cost: 35.0.into(),
+                maintenance: 1.0.into(),
+                required_resources: BTreeMap::new(),
+                effects: vec![
+                    CityEffect::AddHappiness(3),
+                ],
+            },
+            BuildingType {
+                id: s.generator.next(),
+                name: "Forge".into(),
+                production_cost: 40.0.into(),
+                maintenance: 1.0.into(),
+                required_resources: vec![(ResourceType::Iron, 1)].into_iter().collect(),
+                effects: vec![
+                    CityEffect::MulYield(YieldMultiplier { multiplier: 1.15.into(), yield_type: YieldType::Production }),
+                ],
+            },
+            BuildingType {
+                id: s.generator.next(),
+                name: "Monument".into(),
+                production_cost: 20.0.into(),
+                maintenance: 1.0.into(),
+                required_resources: BTreeMap::new(),
+                effects: vec![
+                    CityEffect::AddYield(Yield { value: 2.0.into(), yield_type: YieldType::Culture }),
+                ],
+            },
+            BuildingType {
+                id: s.generator.next(),
+                name: "Amphitheater".into(),
+                production_cost: 40.0.into(),
+                maintenance: 2.0.into(),
+                required_resources: BTreeMap::new(),
+                effects: vec![
+                    CityEffect::AddYield(Yield { value: 3.0.into(), yield_type: YieldType::Culture }),
+                    CityEffect::MulYield(YieldMultiplier { multiplier: 1.25.into(), yield_type: YieldType::Culture }),
+                ],
+            },
         ];
 
         for x in building_types.into_iter() {
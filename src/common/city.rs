@@ -1,4 +1,4 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, VecDeque};
 
 use crate::common::*;
 
@@ -33,6 +33,8 @@ pub enum CityEffect {
     AddYield(Yield),
     MulYield(YieldMultiplier),
     AddTileYield { yield_: Yield, matcher: TileMatcher },
+    // Counts towards the owning civilization's happiness.
+    AddHappiness(isize),
 }
 
 impl CityEffect {
@@ -43,6 +45,7 @@ impl CityEffect {
             CityEffect::AddTileYield { .. } => 1,
             CityEffect::AddYield(..) => 2,
             CityEffect::MulYield(..) => 3,
+            CityEffect::AddHappiness(..) => 4,
         }
     }
 
@@ -62,6 +65,7 @@ impl CityEffect {
                     }
                 }
             }
+            CityEffect::AddHappiness(..) => {}
         }
     }
 }
@@ -73,6 +77,7 @@ impl std::fmt::Display for CityEffect {
             CityEffect::AddYield(x) => write!(f, "{}\n", x)?,
             CityEffect::MulYield(x) => write!(f, "{}\n", x)?,
             CityEffect::AddTileYield { yield_, matcher } => write!(f, "{} for {}\n", yield_, matcher)?,
+            CityEffect::AddHappiness(x) => write!(f, "+{} Happiness\n", x)?,
         }
         Ok(())
     }
@@ -82,6 +87,8 @@ pub struct CityArgs<'a> {
     pub map: &'a mut GameMap,
     pub building_types: &'a BuildingTypes,
     pub tech_progress: &'a TechProgress,
+    // The owner's stockpile, which gates items with resource requirements.
+    pub strategic_resources: &'a BTreeMap<ResourceType, u16>,
     pub unit_templates: &'a UnitTemplates,
 }
 
@@ -89,19 +96,32 @@ pub struct CityArgs<'a> {
 pub struct City {
     pub (in crate::common) id: CityId,
     pub (in crate::common) owner: CivilizationId,
+    // The civilization that founded the city.
+    pub (in crate::common) original_owner: CivilizationId,
+    // Set on the first city each civilization founds, even after it is captured.
+    pub (in crate::common) capital: bool,
     pub (in crate::common) position: TilePosition,
     pub (in crate::common) name: String,
 
     // unit being produced and the amount of production put into it
     pub (in crate::common) producing: Option<(ProducingItem, YieldValue)>,
+    // Items to start producing once the current one is finished, in order.
+    pub (in crate::common) production_queue: VecDeque<ProducingItem>,
+    // Progress on items the city switched away from. Decays every turn.
+    stored_production: BTreeMap<ProducingItemId, YieldValue>,
+    // Production spent beyond the cost of the last finished item, put towards the next one.
+    overflow_production: YieldValue,
 
     pub (in crate::common) population: i16,
+    // Razing cities lose a citizen each turn until they are destroyed.
+    pub (in crate::common) razing: bool,
     // TODO make workable_territory
     pub (in crate::common) territory: BTreeMap<TilePosition, Option<Citizen>>,
     // Generated from territory and cached for perf
     pub (in crate::common) borders: Vec<EdgePosition>,
 
-    turns_until_territory_growth: isize,
+    // Spent to claim a new tile once it reaches the required amount.
+    accumulated_culture: YieldValue,
 
     yields: Yields,
 
@@ -117,7 +137,17 @@ pub struct City {
 }
 
 impl City {
-    const TERRITORY_EXPAND_TURNS: isize = 6;
+    const BASE_CULTURE: f32 = 2.0;
+    // Each claimed tile makes the next one more expensive.
+    const TERRITORY_BASE_CULTURE: f32 = 6.0;
+    const TERRITORY_CULTURE_PER_TILE: f32 = 1.0;
+    const PURCHASE_GOLD_PER_PRODUCTION: f32 = 2.0;
+    const STORED_PRODUCTION_DECAY: f32 = 0.9;
+    // Territory never extends further than this from the city centre.
+    pub const MAX_TERRITORY_DISTANCE: MapUnit = 3;
+    const TILE_BASE_GOLD_COST: f32 = 10.0;
+    const TILE_GOLD_COST_PER_DISTANCE: f32 = 10.0;
+    const TILE_GOLD_COST_PER_OWNED_TILE: f32 = 2.0;
 
     pub fn new(id: CityId, owner: CivilizationId, position: TilePosition, name: String, args: CityArgs) -> Self {
         let mut territory = BTreeMap::new();
@@ -133,12 +163,18 @@ impl City {
         let mut city = City {
             position,
             owner,
+            original_owner: owner,
+            capital: false,
             name,
             id,
             population: 1,
+            razing: false,
             producing: None,
+            production_queue: VecDeque::new(),
+            stored_production: BTreeMap::new(),
+            overflow_production: 0.0.into(),
             territory,
-            turns_until_territory_growth: Self::TERRITORY_EXPAND_TURNS,
+            accumulated_culture: 0.0.into(),
             buildings: BTreeMap::new(),
 
             // Calculated in the update() call below
@@ -162,26 +198,47 @@ impl City {
         self.owner
     }
 
+    pub fn original_owner(&self) -> CivilizationId {
+        self.original_owner
+    }
+
     pub fn position(&self) -> TilePosition {
         self.position
     }
 
+    pub fn razing(&self) -> bool {
+        self.razing
+    }
+
+    pub fn is_capital(&self) -> bool {
+        self.capital
+    }
+
+    // Civilizations can't raze the cities they founded, or anyone's capital.
+    pub fn can_be_razed(&self) -> bool {
+        self.owner != self.original_owner && !self.capital
+    }
+
     pub fn name(&self) -> &String {
         &self.name
     }
 
-    pub (in crate::common) fn on_turn_start(&mut self, args: CityArgs) {
+    pub (in crate::common) fn on_turn_start(&mut self, args: CityArgs, unhappy: bool) {
         self.update(args);
 
         if let Some((_, ref mut spent)) = &mut self.producing {
             *spent += self.yields.production;
         }
 
-        self.accumulated_food += self.yields.food;
+        for stored in self.stored_production.values_mut() {
+            *stored *= Self::STORED_PRODUCTION_DECAY.into();
+        }
+        self.stored_production.retain(|_, stored| *stored >= 1.0.into());
 
-        if self.turns_until_territory_growth > 0 {
-            self.turns_until_territory_growth -= 1;
+        if !unhappy {
+            self.accumulated_food += self.yields.food;
         }
+        self.accumulated_culture += self.yields.culture;
     }
 
     pub fn accumulated_food(&self) -> YieldValue {
@@ -200,12 +257,30 @@ impl City {
         self.accumulated_food >= self.required_food_for_population_increase
     }
 
+    pub fn happiness_from_buildings(&self) -> isize {
+        self.effects
+            .iter()
+            .map(|effect| match effect {
+                CityEffect::AddHappiness(happiness) => *happiness,
+                _ => 0,
+            })
+            .sum()
+    }
+
+    pub fn accumulated_culture(&self) -> YieldValue {
+        self.accumulated_culture
+    }
+
+    pub fn required_culture_for_territory_growth(&self) -> YieldValue {
+        (Self::TERRITORY_BASE_CULTURE + Self::TERRITORY_CULTURE_PER_TILE * self.territory.len() as f32).into()
+    }
+
     pub fn ready_to_grow_territory(&self) -> bool {
-        self.turns_until_territory_growth <= 0
+        self.accumulated_culture >= self.required_culture_for_territory_growth()
     }
 
     pub fn next_tile_to_expand_to(&self, map: &GameMap) -> Option<TilePosition> {
-        for distance in 2..=3 {
+        for distance in 2..=Self::MAX_TERRITORY_DISTANCE {
             let mut tiles_at_distance: Vec<_> = self.position.neighbors_at_distance(map.width(), map.height(), distance, false)
                 .keys()
                 .map(|pos| *pos)
@@ -221,15 +296,44 @@ impl City {
         None
     }
 
-    pub fn turns_until_territory_growth(&self) -> isize {
-        self.turns_until_territory_growth
+    pub fn turns_until_territory_growth(&self) -> usize {
+        (self.required_culture_for_territory_growth() - self.accumulated_culture).div_to_get_turn_count(self.yields.culture)
     }
 
     pub fn grow_territory(&mut self, position: TilePosition, args: CityArgs) {
         self.territory.insert(position, None);
         args.map.tile_mut(position).territory = Some(Territory { city_id: self.id, city_effect_yields: Yields::default() });
         self.update(args);
-        self.turns_until_territory_growth = Self::TERRITORY_EXPAND_TURNS;
+    }
+
+    pub(in crate::common) fn reset_territory_growth(&mut self) {
+        self.accumulated_culture = 0.0.into();
+    }
+
+    // Gold needed to buy the tile, or None if it can't be bought by this city.
+    // Only unclaimed tiles next to the existing territory can be bought.
+    pub fn tile_purchase_cost(&self, position: TilePosition, map: &GameMap) -> Option<YieldValue> {
+        if !map.has_tile(position) || map.tile(position).territory.is_some() {
+            return None;
+        }
+
+        let distance = self.position.distance_to(position);
+        if distance > Self::MAX_TERRITORY_DISTANCE {
+            return None;
+        }
+
+        let adjacent = position
+            .direct_neighbors(map.width(), map.height())
+            .iter()
+            .any(|pos| self.territory.contains_key(pos));
+        if !adjacent {
+            return None;
+        }
+
+        let cost = Self::TILE_BASE_GOLD_COST
+            + Self::TILE_GOLD_COST_PER_DISTANCE * distance as f32
+            + Self::TILE_GOLD_COST_PER_OWNED_TILE * self.territory.len() as f32;
+        Some(cost.into())
     }
 
     pub(in crate::common) fn increase_population_from_food(&mut self, args: CityArgs) {
@@ -238,10 +342,86 @@ impl City {
         self.update(args);
     }
 
+    pub(in crate::common) fn decrease_population(&mut self, args: CityArgs) {
+        self.population -= 1;
+
+        // Unlock a citizen if there are now more locked citizens than people.
+        if self.locked_citizen_count() > self.population {
+            let locked_citizen = self.territory.values_mut().find(|citizen| **citizen == Some(Citizen::Locked)).unwrap();
+            *locked_citizen = None;
+        }
+
+        self.update(args);
+    }
+
+    pub(in crate::common) fn capture(&mut self, new_owner: CivilizationId, args: CityArgs) {
+        self.owner = new_owner;
+        self.razing = false;
+        self.producing = None;
+        self.production_queue.clear();
+        self.stored_production.clear();
+        self.overflow_production = 0.0.into();
+        self.update(args);
+    }
+
     pub fn producing(&self) -> &Option<(ProducingItem, YieldValue)> {
         &self.producing
     }
 
+    // Keeps the progress on the item being switched away from and resumes any progress on the new one.
+    pub(in crate::common) fn set_producing(&mut self, producing: Option<ProducingItem>) {
+        if let Some((item, spent)) = self.producing.take() {
+            if spent > 0.0.into() {
+                self.stored_production.insert(item.id(), spent);
+            }
+        }
+
+        if let Some(item) = producing {
+            let spent = self.stored_production.remove(&item.id()).unwrap_or_default() + self.overflow_production;
+            self.overflow_production = 0.0.into();
+            self.producing = Some((item, spent));
+        }
+    }
+
+    pub(in crate::common) fn finish_producing(&mut self) {
+        let (item, spent) = self.producing.take().unwrap();
+        let overflow = spent - item.production_cost();
+        if overflow > 0.0.into() {
+            self.overflow_production += overflow;
+        }
+    }
+
+    pub fn stored_production(&self, item: &ProducingItemId) -> YieldValue {
+        self.stored_production.get(item).copied().unwrap_or_default()
+    }
+
+    pub fn overflow_production(&self) -> YieldValue {
+        self.overflow_production
+    }
+
+    pub fn production_queue(&self) -> &VecDeque<ProducingItem> {
+        &self.production_queue
+    }
+
+    pub fn can_produce(&self, item: &ProducingItemId) -> bool {
+        match item {
+            ProducingItemId::Unit(unit_template_id) => self.producible_units().any(|id| id == unit_template_id),
+            ProducingItemId::Building(building_type_id) => self.producible_buildings().any(|b| b.id == *building_type_id),
+        }
+    }
+
+    // Gold needed to finish the current production immediately.
+    pub fn purchase_cost(&self) -> Option<YieldValue> {
+        self.producing.as_ref().map(|(producing, spent)| {
+            let remaining = producing.production_cost() - *spent;
+            if remaining > 0.0.into() {
+                remaining * Self::PURCHASE_GOLD_PER_PRODUCTION.into()
+            } else {
+                0.0.into()
+            }
+        })
+    }
+
     pub fn territory(&self) -> &BTreeMap<TilePosition, Option<Citizen>> {
         &self.territory
     }
@@ -312,7 +492,9 @@ impl City {
     }
 
     fn update_yields(&mut self, map: &GameMap) {
-        let pop_yields = Yields::default().with_science(self.population as f32);
+        let pop_yields = Yields::default()
+            .with_science(self.population as f32)
+            .with_culture(Self::BASE_CULTURE);
         let tile_yields = self.territory
             .iter()
             .filter(|(pos, citizen)| **pos == self.position || citizen.is_some())
@@ -322,23 +504,47 @@ impl City {
         self.yields = pop_yields + tile_yields;
     }
 
-    fn update_producible_buildings(&mut self, building_types: &BuildingTypes, tech_progress: &TechProgress) {
+    fn update_producible_buildings(
+        &mut self,
+        building_types: &BuildingTypes,
+        tech_progress: &TechProgress,
+        strategic_resources: &BTreeMap<ResourceType, u16>,
+    ) {
         self.producible_buildings = tech_progress
             .unlocked_buildings()
             .iter()
             .filter(|building_type| !self.buildings.contains_key(&building_type))
             .map(|building_type| building_types.get(*building_type).clone())
+            .filter(|building_type| has_strategic_resources(strategic_resources, &building_type.required_resources))
             .collect();
     }
 
-    fn update_producible_units(&mut self, tech_progress: &TechProgress) {
+    fn update_producible_units(
+        &mut self,
+        unit_templates: &UnitTemplates,
+        map: &GameMap,
+        tech_progress: &TechProgress,
+        strategic_resources: &BTreeMap<ResourceType, u16>,
+    ) {
+        let coastal = self.is_coastal(map);
         self.producible_units = tech_progress
             .unlocked_units()
             .iter()
+            .filter(|unit_template_id| coastal || unit_templates.get(**unit_template_id).domain != UnitDomain::Sea)
+            .filter(|unit_template_id| {
+                has_strategic_resources(strategic_resources, &unit_templates.get(**unit_template_id).required_resources)
+            })
             .map(|unit_template_id| *unit_template_id)
             .collect();
     }
 
+    pub fn is_coastal(&self, map: &GameMap) -> bool {
+        self.position
+            .direct_neighbors(map.width(), map.height())
+            .into_iter()
+            .any(|position| map.tile(position).is_water())
+    }
+
     fn update_effects(&mut self) {
         self.effects = self.buildings
             .values()
@@ -366,8 +572,8 @@ impl City {
         self.update_borders_from_territory();
         self.update_citizens(args.map);
         self.update_yields(args.map);
-        self.update_producible_buildings(args.building_types, args.tech_progress);
-        self.update_producible_units(args.tech_progress);
+        self.update_producible_buildings(args.building_types, args.tech_progress, args.strategic_resources);
+        self.update_producible_units(args.unit_templates, args.map, args.tech_progress, args.strategic_resources);
         self.update_effects();
         self.apply_effects(args.map);
 
@@ -384,6 +590,11 @@ impl City {
         self.update(args);
     }
 
+    pub(in crate::common) fn remove_building(&mut self, building_type_id: BuildingTypeId, args: CityArgs) {
+        self.buildings.remove(&building_type_id);
+        self.update(args);
+    }
+
     pub fn buildings(&self) -> impl Iterator<Item = &BuildingType> {
         self.buildings.values()
     }
@@ -0,0 +1,76 @@
+use serde::{Serialize, Deserialize};
+
+use crate::common::*;
+
+// Influence a civilization needs with a city-state to befriend it, or to become its ally.
+pub const CITY_STATE_FRIEND_INFLUENCE: u16 = 30;
+pub const CITY_STATE_ALLY_INFLUENCE: u16 = 60;
+// Influence slowly wears off unless it is topped up.
+pub const CITY_STATE_INFLUENCE_DECAY: u16 = 1;
+pub const CITY_STATE_GOLD_GIFT: f32 = 25.0;
+pub const CITY_STATE_GOLD_GIFT_INFLUENCE: u16 = 15;
+// Only soldiers can be gifted.
+pub const CITY_STATE_UNIT_GIFT_INFLUENCE: u16 = 20;
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum CityStateType {
+    Scientific,
+    Mercantile,
+}
+
+impl CityStateType {
+    pub fn random() -> Self {
+        if rand::random::<bool>() { Self::Scientific } else { Self::Mercantile }
+    }
+
+    // Yields given every turn to civilizations the city-state is friendly with.
+    pub fn bonus_yields(self, relationship: CityStateRelationship) -> Yields {
+        let amount = match relationship {
+            CityStateRelationship::Neutral => return Yields::default(),
+            CityStateRelationship::Friend => 2.0,
+            CityStateRelationship::Ally => 4.0,
+        };
+        match self {
+            Self::Scientific => Yields::default().with_science(amount),
+            Self::Mercantile => Yields::default().with_gold(amount),
+        }
+    }
+}
+
+impl std::fmt::Display for CityStateType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Scientific => "Scientific",
+            Self::Mercantile => "Mercantile",
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CityStateRelationship {
+    Neutral,
+    Friend,
+    Ally,
+}
+
+impl CityStateRelationship {
+    pub fn from_influence(influence: u16) -> Self {
+        if influence >= CITY_STATE_ALLY_INFLUENCE {
+            Self::Ally
+        } else if influence >= CITY_STATE_FRIEND_INFLUENCE {
+            Self::Friend
+        } else {
+            Self::Neutral
+        }
+    }
+}
+
+impl std::fmt::Display for CityStateRelationship {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Neutral => "Neutral",
+            Self::Friend => "Friend",
+            Self::Ally => "Ally",
+        })
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use serde::{Serialize, Deserialize};
 
 use crate::common::*;
@@ -20,6 +22,7 @@ const AMETHYST: CivilizationColor = CivilizationColor { r: 136, g: 102, b: 204 }
 const MEDIUM_BLUE: CivilizationColor = CivilizationColor { r: 68, g: 6, b: 194 };
 const SHEEN_GREEN: CivilizationColor = CivilizationColor { r: 140, g: 207, b: 8 };
 const PINK: CivilizationColor = CivilizationColor { r: 252, g: 81, b: 147 };
+const CHARCOAL: CivilizationColor = CivilizationColor { r: 54, g: 54, b: 54 };
 
 const COLORS: &[CivilizationColor] = &[
     RUBY_RED,
@@ -32,6 +35,15 @@ const COLORS: &[CivilizationColor] = &[
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct CivilizationId(u8);
 
+impl CivilizationId {
+    // Reserved for the barbarian faction, which isn't controlled by any player.
+    pub const BARBARIANS: CivilizationId = CivilizationId(u8::MAX);
+
+    pub fn is_barbarian(self) -> bool {
+        self == Self::BARBARIANS
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CivilizationIdGenerator {
     next_civilization_id: u8,
@@ -54,18 +66,48 @@ pub struct Civilization {
     id: CivilizationId,
     player_name: String,
     pub(in crate::common) tech_progress: TechProgress,
+    pub(in crate::common) gold: YieldValue,
+    pub(in crate::common) strategic_resources: BTreeMap<ResourceType, u16>,
+    // Set once the civilization has lost all of its cities and units.
+    pub(in crate::common) eliminated: bool,
+    // Set for minor civilizations, which aren't controlled by any player.
+    city_state: Option<CityStateType>,
+    // For city-states, how much each civilization has befriended it.
+    pub(in crate::common) influence: BTreeMap<CivilizationId, u16>,
 }
 
 impl Civilization {
+    // Covers upkeep until the first city is founded.
+    const STARTING_GOLD: f32 = 10.0;
+
     pub fn new<S: Into<String>>(id: CivilizationId, player_name: S, tech_tree: &TechTree) -> Self {
         Self {
             id,
             player_name: player_name.into(),
             tech_progress: TechProgress::new(tech_tree),
+            gold: Self::STARTING_GOLD.into(),
+            strategic_resources: BTreeMap::new(),
+            eliminated: false,
+            city_state: None,
+            influence: BTreeMap::new(),
+        }
+    }
+
+    pub fn new_city_state<S: Into<String>>(id: CivilizationId, name: S, city_state_type: CityStateType, tech_tree: &TechTree) -> Self {
+        Self {
+            city_state: Some(city_state_type),
+            ..Self::new(id, name, tech_tree)
         }
     }
 
+    pub fn new_barbarians(tech_tree: &TechTree) -> Self {
+        Self::new(CivilizationId::BARBARIANS, "Barbarians", tech_tree)
+    }
+
     pub fn color(&self) -> CivilizationColor {
+        if self.is_barbarian() {
+            return CHARCOAL;
+        }
         COLORS[self.id.0 as usize % COLORS.len()]
     }
 
@@ -73,15 +115,65 @@ impl Civilization {
         self.id
     }
 
+    pub fn is_barbarian(&self) -> bool {
+        self.id.is_barbarian()
+    }
+
+    pub fn is_city_state(&self) -> bool {
+        self.city_state.is_some()
+    }
+
+    pub fn city_state_type(&self) -> Option<CityStateType> {
+        self.city_state
+    }
+
+    pub fn influence(&self, civilization_id: CivilizationId) -> u16 {
+        self.influence.get(&civilization_id).copied().unwrap_or(0)
+    }
+
     pub fn player_name(&self) -> &String {
         &self.player_name
     }
 
+    pub fn eliminated(&self) -> bool {
+        self.eliminated
+    }
+
     pub fn tech_progress(&self) -> &TechProgress {
         &self.tech_progress
     }
 
-    pub fn on_turn_start(&mut self, science_yield: YieldValue) {
+    pub fn gold(&self) -> YieldValue {
+        self.gold
+    }
+
+    pub fn strategic_resources(&self) -> &BTreeMap<ResourceType, u16> {
+        &self.strategic_resources
+    }
+
+    pub fn has_strategic_resources(&self, required: &BTreeMap<ResourceType, u16>) -> bool {
+        has_strategic_resources(&self.strategic_resources, required)
+    }
+
+    pub(in crate::common) fn spend_strategic_resources(&mut self, resources: &BTreeMap<ResourceType, u16>) {
+        for (resource, amount) in resources {
+            let stock = self.strategic_resources.entry(*resource).or_insert(0);
+            *stock = stock.saturating_sub(*amount);
+        }
+    }
+
+    pub fn on_turn_start(&mut self, science_yield: YieldValue, gold_income: YieldValue, strategic_income: &BTreeMap<ResourceType, u16>) {
         self.tech_progress.on_turn_start(science_yield);
+        self.gold += gold_income;
+        for (resource, amount) in strategic_income {
+            *self.strategic_resources.entry(*resource).or_insert(0) += *amount;
+        }
+        for influence in self.influence.values_mut() {
+            *influence = influence.saturating_sub(CITY_STATE_INFLUENCE_DECAY);
+        }
     }
 }
+
+pub fn has_strategic_resources(stock: &BTreeMap<ResourceType, u16>, required: &BTreeMap<ResourceType, u16>) -> bool {
+    required.iter().all(|(resource, amount)| stock.get(resource).map(|n| n >= amount).unwrap_or(false))
+}
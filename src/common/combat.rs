@@ -0,0 +1,43 @@
+use crate::common::*;
+
+// Damage dealt between two units of equal strength.
+const BASE_DAMAGE: f32 = 30.0;
+pub const ATTACK_EXPERIENCE: u16 = 5;
+pub const DEFEND_EXPERIENCE: u16 = 4;
+
+// Combat is deterministic so that players can predict the outcome of an attack.
+#[derive(Clone, Copy, Debug)]
+pub struct CombatResult {
+    pub attacker_damage: u16,
+    pub defender_damage: u16,
+}
+
+impl CombatResult {
+    pub fn calculate(attacker: &Unit, defender: &Unit, map: &GameMap) -> Self {
+        let tile = map.tile(defender.position());
+        let attacker_strength = combat_strength(attacker, tile);
+        let defender_strength = combat_strength(defender, tile) * tile_defence_multiplier(tile) * (1.0 + defender.fortification_bonus());
+
+        Self {
+            attacker_damage: damage(defender_strength, attacker_strength).min(attacker.health()),
+            defender_damage: damage(attacker_strength, defender_strength).min(defender.health()),
+        }
+    }
+}
+
+// Damaged units fight less effectively.
+fn combat_strength(unit: &Unit, tile: &Tile) -> f32 {
+    unit.strength() as f32 * (0.5 + 0.5 * unit.health() as f32 / unit.max_health() as f32) * (1.0 + unit.combat_bonus(tile))
+}
+
+fn tile_defence_multiplier(tile: &Tile) -> f32 {
+    match tile.vegetation {
+        Some(Vegetation::Forest) | Some(Vegetation::Jungle) => 1.25,
+        None => 1.0,
+    }
+}
+
+fn damage(strength: f32, opponent_strength: f32) -> u16 {
+    let ratio = strength / opponent_strength;
+    (BASE_DAMAGE * ratio.powf(1.5)).round().max(1.0) as u16
+}
@@ -0,0 +1,95 @@
+use std::collections::BTreeMap;
+
+use crate::common::*;
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DiplomaticState {
+    War,
+    Peace,
+    OpenBorders,
+    Alliance,
+}
+
+impl DiplomaticState {
+    // Whether units may enter the other civilization's territory.
+    pub fn allows_passage(self) -> bool {
+        match self {
+            Self::War | Self::OpenBorders | Self::Alliance => true,
+            Self::Peace => false,
+        }
+    }
+
+    // War is declared rather than proposed. Otherwise, treaties can only be proposed one step at a time.
+    pub fn can_propose_from(self, current: Self) -> bool {
+        match self {
+            Self::War => false,
+            Self::Peace => current == Self::War,
+            Self::OpenBorders => current == Self::Peace,
+            Self::Alliance => current == Self::Peace || current == Self::OpenBorders,
+        }
+    }
+}
+
+impl std::fmt::Display for DiplomaticState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::War => "War",
+            Self::Peace => "Peace",
+            Self::OpenBorders => "Open Borders",
+            Self::Alliance => "Alliance",
+        })
+    }
+}
+
+// Relationships between each pair of civilizations. Pairs that haven't been recorded are at peace.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Diplomacy {
+    // Keyed by the pair in ascending order.
+    states: BTreeMap<(CivilizationId, CivilizationId), DiplomaticState>,
+    // (from, to) -> proposed state
+    proposals: BTreeMap<(CivilizationId, CivilizationId), DiplomaticState>,
+}
+
+impl Diplomacy {
+    fn key(a: CivilizationId, b: CivilizationId) -> (CivilizationId, CivilizationId) {
+        if a < b { (a, b) } else { (b, a) }
+    }
+
+    pub fn state(&self, a: CivilizationId, b: CivilizationId) -> DiplomaticState {
+        if a == b {
+            return DiplomaticState::Alliance;
+        }
+        self.states.get(&Self::key(a, b)).copied().unwrap_or(DiplomaticState::Peace)
+    }
+
+    pub fn at_war(&self, a: CivilizationId, b: CivilizationId) -> bool {
+        self.state(a, b) == DiplomaticState::War
+    }
+
+    pub fn proposal(&self, from: CivilizationId, to: CivilizationId) -> Option<DiplomaticState> {
+        self.proposals.get(&(from, to)).copied()
+    }
+
+    // Proposals made to the civilization, by proposer.
+    pub fn proposals_to(&self, to: CivilizationId) -> impl Iterator<Item = (CivilizationId, DiplomaticState)> + '_ {
+        self.proposals
+            .iter()
+            .filter(move |((_, proposal_to), _)| *proposal_to == to)
+            .map(|((from, _), state)| (*from, *state))
+    }
+
+    pub(in crate::common) fn set_state(&mut self, a: CivilizationId, b: CivilizationId, state: DiplomaticState) {
+        self.states.insert(Self::key(a, b), state);
+        // Any pending proposals between the pair are now out of date.
+        self.proposals.remove(&(a, b));
+        self.proposals.remove(&(b, a));
+    }
+
+    pub(in crate::common) fn set_proposal(&mut self, from: CivilizationId, to: CivilizationId, state: Option<DiplomaticState>) {
+        if let Some(state) = state {
+            self.proposals.insert((from, to), state);
+        } else {
+            self.proposals.remove(&(from, to));
+        }
+    }
+}
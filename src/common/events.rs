@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use crate::common::*;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -9,16 +11,51 @@ pub enum GameEventType {
     RenameCity { city_id: CityId, name: String },
     SetPlayerReady { player_id: PlayerId, ready: bool },
     SetProducing { city_id: CityId, producing: Option<ProducingItemId> },
+    FinishProducing { city_id: CityId },
+    EnqueueProduction { city_id: CityId, item: ProducingItemId },
+    MoveQueuedProduction { city_id: CityId, from: usize, to: usize },
+    RemoveQueuedProduction { city_id: CityId, index: usize },
     NewUnit { template: UnitTemplate, owner: CivilizationId, position: TilePosition, unit_id: UnitId },
     NewBuilding { building_type_id: BuildingTypeId, city_id: CityId },
+    RemoveBuilding { building_type_id: BuildingTypeId, city_id: CityId },
     Crash { message: String },
-    SetSleeping { unit_id: UnitId, sleeping: bool },
+    SetUnitOrder { unit_id: UnitId, order: Option<UnitOrder> },
     SetCitizenLocked { city_id: CityId, position: TilePosition, locked: bool },
     IncreasePopulationFromFood { city_id: CityId },
     AddTerritoryToCity { city_id: CityId, position: TilePosition },
     Harvest { position: TilePosition },
     DepleteMovement { unit_id: UnitId },
     UseCharge { unit_id: UnitId },
+    // None cancels the unit's improvement order.
+    SetImprovementOrder { unit_id: UnitId, improvement_type_id: Option<ImprovementTypeId> },
+    // Builds the ordered improvement on the unit's tile.
+    FinishImprovement { unit_id: UnitId },
     FinishResearch { civilization_id: CivilizationId },
     SetResearch { civilization_id: CivilizationId, tech_id: TechId },
+    DamageUnit { unit_id: UnitId, damage: u16 },
+    AddExperience { unit_id: UnitId, amount: u16 },
+    PromoteUnit { unit_id: UnitId, promotion_id: PromotionId },
+    UpgradeUnit { unit_id: UnitId, template: UnitTemplate },
+    CaptureCity { city_id: CityId, owner: CivilizationId },
+    SetRazing { city_id: CityId, razing: bool },
+    DecreasePopulationFromRazing { city_id: CityId },
+    DestroyCity { city_id: CityId },
+    EliminateCivilization { civilization_id: CivilizationId },
+    SetMovePath { unit_id: UnitId, path: Vec<TilePosition> },
+    SpendGold { civilization_id: CivilizationId, amount: YieldValue },
+    SetDiplomaticState { civilization_a: CivilizationId, civilization_b: CivilizationId, state: DiplomaticState },
+    // None withdraws the proposal.
+    SetDiplomaticProposal { from: CivilizationId, to: CivilizationId, state: Option<DiplomaticState> },
+    EstablishTradeRoute { route: TradeRoute },
+    CancelTradeRoute { route: TradeRoute },
+    AddInfluence { city_state_id: CivilizationId, civilization_id: CivilizationId, amount: u16 },
+    // Hands the unit over to the city-state.
+    GiftUnit { unit_id: UnitId, civilization_id: CivilizationId },
+    NewBarbarianCamp { position: TilePosition },
+    GameOver { winners: Vec<CivilizationId>, victory_type: VictoryType },
+    // Removes the camp and rewards the civilization that cleared it.
+    ClearBarbarianCamp { position: TilePosition, civilization_id: CivilizationId },
+    SpendStrategicResources { civilization_id: CivilizationId, resources: BTreeMap<ResourceType, u16> },
+    // Unlike AddTerritoryToCity, this doesn't delay the city's natural territory growth.
+    BuyTile { city_id: CityId, position: TilePosition },
 }
@@ -1,10 +1,12 @@
 use std::cmp::{Ordering, Reverse};
-use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap};
 
 use crate::common::*;
 
 pub type MapUnit = i16;
 
+const RIVER_CROSSING_COST: MapUnit = 1;
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct GameMap {
     // Number of tiles
@@ -31,6 +33,7 @@ impl GameMap {
                     resource: None,
                     vegetation: None,
                     harvested: false,
+                    improvement: None,
                 });
             }
             tile_cols.push(tile_col);
@@ -90,49 +93,121 @@ impl GameMap {
         }
         modified
     }
-    pub fn shortest_path(&mut self,s:TilePosition, d:TilePosition) -> Option<Vec<TilePosition>> { // Move this to game_map.rs?
-        // Find shortest path using A* algorithm
+
+    pub fn rivers(&self) -> BTreeSet<CanonicalEdgePosition> {
+        self.tiles()
+            .flat_map(|tile| tile.rivers.iter().map(move |edge| EdgePosition(tile.position, *edge).canonical()))
+            .collect()
+    }
+
+    // Movement spent moving between two adjacent tiles, or None if the destination is impassable.
+    pub fn movement_cost(&self, from: TilePosition, to: TilePosition, domain: UnitDomain, can_embark: bool) -> Option<MapUnit> {
+        let mut cost = self.tile(to).movement_cost(domain, can_embark)?;
+        if let (UnitDomain::Land, Some(edge)) = (domain, from.edge_to(to)) {
+            if self.tile(from).rivers.contains(&edge) {
+                cost += RIVER_CROSSING_COST;
+            }
+        }
+        Some(cost)
+    }
+
+    // Maps each tile reachable with the given movement to the most movement that can be left after reaching it.
+    // Entering a tile always succeeds if there is any movement left, even if it costs more than what remains.
+    // Entering a tile for which ends_movement returns true uses up all remaining movement.
+    pub fn reachable_tiles(
+        &self,
+        start: TilePosition,
+        movement: MapUnit,
+        cost: impl Fn(TilePosition, TilePosition) -> Option<MapUnit>,
+        ends_movement: impl Fn(TilePosition) -> bool,
+    ) -> BTreeMap<TilePosition, MapUnit> {
+        let mut remaining = BTreeMap::new();
         let mut open_nodes = BinaryHeap::new();
-        let mut visited_nodes = HashSet::new();
-        let mut came_from = HashMap::new();
-        let mut g_score = HashMap::new();
 
-        let init_d = s.distance_to(d);
-        g_score.insert(s,0);
+        remaining.insert(start, movement);
+        open_nodes.push((movement, start));
+
+        while let Some((current_remaining, current_node)) = open_nodes.pop() {
+            if current_remaining <= 0 || current_remaining < remaining[&current_node] {
+                continue;
+            }
 
-        open_nodes.push((Reverse(init_d),s));
+            for neighbor in current_node.direct_neighbors(self.width(), self.height()) {
+                let step_cost = if let Some(cost) = cost(current_node, neighbor) { cost } else { continue };
+                let neighbor_remaining = if ends_movement(neighbor) { 0 } else { (current_remaining - step_cost).max(0) };
 
-        while !open_nodes.is_empty() {
-            let current_node = open_nodes.peek().unwrap().1;
-            if current_node == d {
-                let mut path = Vec::new();
-                let mut counter = &current_node;
-                while let Some(mp) = came_from.get(counter) {
-                    path.push(*mp);
-                    counter = &mp;
+                if remaining.get(&neighbor).map(|r| neighbor_remaining > *r).unwrap_or(true) {
+                    remaining.insert(neighbor, neighbor_remaining);
+                    open_nodes.push((neighbor_remaining, neighbor));
                 }
-                return Some(path);
             }
+        }
 
-            open_nodes.pop();
-            let neigh = current_node.neighbors_at_distance(self.width(), self.height(),2,false); // may be different for different units
+        remaining
+    }
 
-            for (n,_one) in neigh {
-                let temp = g_score.get(&current_node).unwrap()+1; // may need to change this when adding hills
-                if let Some(neighg) = g_score.get(&n) {
-                    if temp > *neighg {
-                        continue;
+    // Finds the cheapest path using A*. The path excludes the start and includes the destination.
+    // Movement is spent from what is left this turn, then from the total movement of each following turn.
+    // Entering a tile for which ends_movement returns true uses up the rest of that turn's movement.
+    pub fn shortest_path(
+        &self,
+        start: TilePosition,
+        destination: TilePosition,
+        movement: MapUnit,
+        total_movement: MapUnit,
+        cost: impl Fn(TilePosition, TilePosition) -> Option<MapUnit>,
+        ends_movement: impl Fn(TilePosition) -> bool,
+    ) -> Option<Vec<TilePosition>> {
+        if start == destination {
+            return Some(Vec::new());
+        }
+
+        let total_movement = total_movement.max(1);
+        let end_of_turn = |spent: MapUnit| {
+            if spent <= movement {
+                movement
+            } else {
+                movement + (spent - movement + total_movement - 1) / total_movement * total_movement
+            }
+        };
+
+        let mut open_nodes = BinaryHeap::new();
+        let mut came_from = HashMap::new();
+        let mut g_score = HashMap::new();
+
+        g_score.insert(start, 0);
+        open_nodes.push((Reverse(start.distance_to(destination)), start));
+
+        while let Some((_, current_node)) = open_nodes.pop() {
+            if current_node == destination {
+                let mut path = vec![current_node];
+                let mut counter = current_node;
+                while let Some(previous) = came_from.get(&counter) {
+                    if *previous != start {
+                        path.push(*previous);
                     }
+                    counter = *previous;
                 }
+                path.reverse();
+                return Some(path);
+            }
 
-                came_from.insert(n,current_node);
-                g_score.insert(n,temp);
-                if !visited_nodes.contains(&n) {
-                    visited_nodes.insert(n);
-                    open_nodes.push((Reverse(temp+n.distance_to(d)),n));
+            let current_g_score = g_score[&current_node];
+            for neighbor in current_node.direct_neighbors(self.width(), self.height()) {
+                let step_cost = if let Some(cost) = cost(current_node, neighbor) { cost } else { continue };
+                let mut neighbor_g_score = current_g_score + step_cost;
+                if ends_movement(neighbor) {
+                    neighbor_g_score = end_of_turn(neighbor_g_score);
+                }
+
+                if g_score.get(&neighbor).map(|g| neighbor_g_score < *g).unwrap_or(true) {
+                    came_from.insert(neighbor, current_node);
+                    g_score.insert(neighbor, neighbor_g_score);
+                    open_nodes.push((Reverse(neighbor_g_score + neighbor.distance_to(destination)), neighbor));
                 }
             }
         }
+
         None
     }
 
@@ -1,13 +1,24 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use serde::{Serialize, Deserialize};
 
 use crate::common::*;
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+const BASE_HAPPINESS: isize = 5;
+const UNHAPPINESS_PER_CITY: isize = 2;
+const HAPPINESS_PER_LUXURY: isize = 4;
+const STRATEGIC_RESOURCE_PER_TILE: u16 = 1;
+const SCORE_PER_CITY: u32 = 10;
+const SCORE_PER_POPULATION: u32 = 4;
+const SCORE_PER_TECH: u32 = 5;
+const SCORE_PER_TILE: u32 = 1;
+// Health restored each turn a unit rests.
+const HEAL_IN_FRIENDLY_TERRITORY: u16 = 20;
+const HEAL_ELSEWHERE: u16 = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum ProducingItemId {
-    // TODO use UnitTemplateId
-    Unit(UnitTemplate),
+    Unit(UnitTemplateId),
     Building(BuildingTypeId),
 }
 
@@ -31,6 +42,21 @@ impl ProducingItem {
             ProducingItem::Building(building) => building.production_cost,
         }
     }
+
+    pub fn required_resources(&self) -> &BTreeMap<ResourceType, u16> {
+        match self {
+            ProducingItem::Unit(unit) => &unit.required_resources,
+            ProducingItem::Building(building) => &building.required_resources,
+        }
+    }
+
+    pub fn id(&self) -> ProducingItemId {
+        match self {
+            ProducingItem::Unit(unit) => ProducingItemId::Unit(unit.id),
+            ProducingItem::Building(building) => ProducingItemId::Building(building.id),
+        }
+    }
+
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -45,6 +71,9 @@ pub struct GameWorld {
 
     turn: u16,
 
+    // Kept so that the map can be exported.
+    pub(in crate::common) start_positions: Vec<TilePosition>,
+
     unit_id_generator: UnitIdGenerator,
     city_name_generator: CityNameGenerator,
     city_id_generator: CityIdGenerator,
@@ -54,13 +83,30 @@ pub struct GameWorld {
 
     building_types: BuildingTypes,
 
+    improvement_types: ImprovementTypes,
+
+    promotions: Promotions,
+
     tech_tree: TechTree,
+
+    diplomacy: Diplomacy,
+
+    trade_routes: BTreeSet<TradeRoute>,
+
+    barbarian_camps: BTreeMap<TilePosition, BarbarianCamp>,
+
+    victory_conditions: VictoryConditions,
+    // Set once someone has won. No further actions are accepted.
+    game_over: Option<GameOutcome>,
+
+    statistics: StatisticsHistory,
 }
 
 impl GameWorld {
     pub fn new(width: MapUnit, height: MapUnit, init_players: Vec<InitPlayer>) -> Self {
         let building_types = BuildingTypes::new();
         let unit_templates = UnitTemplates::new();
+        let improvement_types = ImprovementTypes::new();
 
         let mut game = GameWorld {
             map: GameMap::new(width, height),
@@ -69,22 +115,63 @@ impl GameWorld {
             cities: BTreeMap::new(),
             civilizations: BTreeMap::new(),
             turn: 0,
+            start_positions: vec![],
             unit_id_generator: UnitIdGenerator::new(),
             city_name_generator: CityNameGenerator::new(),
             city_id_generator: CityIdGenerator::new(),
             civilization_id_generator: CivilizationIdGenerator::new(),
-            tech_tree: TechTree::generate(&building_types, &unit_templates),
+            tech_tree: TechTree::generate(&building_types, &unit_templates, &improvement_types),
             unit_templates,
             building_types,
+            improvement_types,
+            promotions: Promotions::new(),
+            diplomacy: Diplomacy::default(),
+            trade_routes: BTreeSet::new(),
+            barbarian_camps: BTreeMap::new(),
+            victory_conditions: VictoryConditions::default(),
+            game_over: None,
+            statistics: StatisticsHistory::default(),
         };
 
         for init_player in init_players {
             game.new_civilization(init_player);
         }
 
+        // Barbarians are at war with everyone, and can't negotiate.
+        for civilization_id in game.civilizations.keys() {
+            game.diplomacy.set_state(*civilization_id, CivilizationId::BARBARIANS, DiplomaticState::War);
+        }
+        let barbarians = Civilization::new_barbarians(&game.tech_tree);
+        game.civilizations.insert(CivilizationId::BARBARIANS, barbarians);
+
         game
     }
 
+    pub fn to_map_file(&self) -> MapFile {
+        MapFile::from_map(&self.map, &self.start_positions)
+    }
+
+    pub fn set_victory_conditions(&mut self, victory_conditions: VictoryConditions) {
+        self.victory_conditions = victory_conditions;
+    }
+
+    pub fn victory_conditions(&self) -> &VictoryConditions {
+        &self.victory_conditions
+    }
+
+    pub fn game_over(&self) -> Option<&GameOutcome> {
+        self.game_over.as_ref()
+    }
+
+    pub fn statistics(&self) -> &StatisticsHistory {
+        &self.statistics
+    }
+
+    pub fn statistics_csv(&self) -> String {
+        let names = self.civilizations().map(|civ| (civ.id(), civ.player_name().clone())).collect();
+        self.statistics.to_csv(&names)
+    }
+
     pub fn tech_tree(&self) -> &TechTree {
         &self.tech_tree
     }
@@ -106,6 +193,82 @@ impl GameWorld {
         &self.unit_templates
     }
 
+    pub fn improvement_types(&self) -> &ImprovementTypes {
+        &self.improvement_types
+    }
+
+    pub fn promotions(&self) -> &Promotions {
+        &self.promotions
+    }
+
+    pub fn diplomacy(&self) -> &Diplomacy {
+        &self.diplomacy
+    }
+
+    pub fn trade_routes(&self) -> impl Iterator<Item = &TradeRoute> {
+        self.trade_routes.iter()
+    }
+
+    pub fn barbarian_camps(&self) -> impl Iterator<Item = &BarbarianCamp> {
+        self.barbarian_camps.values()
+    }
+
+    pub fn barbarian_camp(&self, position: TilePosition) -> Option<&BarbarianCamp> {
+        self.barbarian_camps.get(&position)
+    }
+
+    // Cities the unit could establish a trade route to from the city it is in.
+    pub fn trade_route_destinations(&self, unit: &Unit) -> Vec<CityId> {
+        if !unit.has_ability(UnitAbility::EstablishTradeRoute) {
+            return vec![];
+        }
+        let origin = match self.map.tile(unit.position()).city.and_then(|city_id| self.city(city_id)) {
+            Some(city) if city.owner() == unit.owner() => city,
+            _ => return vec![],
+        };
+
+        self.cities()
+            .filter(|city| city.id() != origin.id())
+            .filter(|city| city.position().distance_to(origin.position()) <= TradeRoute::RANGE)
+            .filter(|city| !self.diplomacy.at_war(unit.owner(), city.owner()))
+            .filter(|city| !self.trade_routes.contains(&TradeRoute { origin: origin.id(), destination: city.id() }))
+            .map(|city| city.id())
+            .collect()
+    }
+
+    // Per-turn yields the city earns from trade routes starting or ending there.
+    pub fn city_trade_yields(&self, city_id: CityId) -> Yields {
+        let mut result = Yields::default();
+
+        for route in self.trade_routes.iter().filter(|route| route.involves(city_id)) {
+            let (origin_yields, destination_yields) = route.yields(
+                self.city(route.origin).unwrap(),
+                self.city(route.destination).unwrap(),
+            );
+            if route.origin == city_id {
+                result += origin_yields;
+            }
+            if route.destination == city_id {
+                result += destination_yields;
+            }
+        }
+
+        result
+    }
+
+    // Owners of the origin and destination cities.
+    fn trade_route_owners(&self, route: &TradeRoute) -> (CivilizationId, CivilizationId) {
+        (self.city(route.origin).unwrap().owner(), self.city(route.destination).unwrap().owner())
+    }
+
+    fn cancel_trade_routes<F: Fn(&Self, &TradeRoute) -> bool>(&mut self, filter: F) -> Vec<GameEventType> {
+        let routes: Vec<_> = self.trade_routes.iter().filter(|route| filter(self, route)).copied().collect();
+        routes
+            .into_iter()
+            .map(|route| self.apply_event_move(GameEventType::CancelTradeRoute { route }))
+            .collect()
+    }
+
     fn new_civilization(&mut self, init_player: InitPlayer) {
         let civilization_id = self.civilization_id_generator.next();
         let civilization = Civilization::new(civilization_id, init_player.name.clone(), &self.tech_tree);
@@ -114,6 +277,53 @@ impl GameWorld {
         self.players.insert(init_player.id, player);
     }
 
+    pub(in crate::common) fn new_city_state(&mut self, position: TilePosition, city_state_type: CityStateType) {
+        let civilization_id = self.civilization_id_generator.next();
+        let name = self.city_name_generator.next();
+        let civilization = Civilization::new_city_state(civilization_id, name.clone(), city_state_type, &self.tech_tree);
+        self.civilizations.insert(civilization_id, civilization);
+        self.diplomacy.set_state(civilization_id, CivilizationId::BARBARIANS, DiplomaticState::War);
+
+        self.new_named_city(civilization_id, position, name);
+        let id = self.next_unit_id();
+        self.new_unit(id, &self.unit_templates.get_by_name("Warrior").clone(), civilization_id, position);
+    }
+
+    pub fn city_state_relationship(&self, city_state_id: CivilizationId, civilization_id: CivilizationId) -> CityStateRelationship {
+        if self.diplomacy.at_war(city_state_id, civilization_id) {
+            return CityStateRelationship::Neutral;
+        }
+        CityStateRelationship::from_influence(self.civilization(city_state_id).unwrap().influence(civilization_id))
+    }
+
+    // Per-turn yields the civilization receives from befriended city-states.
+    pub fn city_state_bonus_yields(&self, civilization_id: CivilizationId) -> Yields {
+        let mut result = Yields::default();
+
+        for city_state in self.civilizations().filter(|civ| !civ.eliminated()) {
+            if let Some(city_state_type) = city_state.city_state_type() {
+                result += city_state_type.bonus_yields(self.city_state_relationship(city_state.id(), civilization_id));
+            }
+        }
+
+        result
+    }
+
+    // The city-state whose territory the unit is in, if the unit can be gifted to it.
+    pub fn city_state_gift_target(&self, unit: &Unit) -> Option<CivilizationId> {
+        if !unit.can_fight() {
+            return None;
+        }
+        let Territory { city_id, .. } = self.map.tile(unit.position()).territory.as_ref()?;
+        let owner = self.city(*city_id).unwrap().owner();
+
+        if self.civilization(owner).unwrap().is_city_state() && !self.diplomacy.at_war(owner, unit.owner()) {
+            Some(owner)
+        } else {
+            None
+        }
+    }
+
     pub fn players(&self) -> impl Iterator<Item = &Player> {
         self.players.iter().map(|(_, v)| v)
     }
@@ -144,6 +354,120 @@ impl GameWorld {
         self.units.get(&unit_id)
     }
 
+    pub fn foreign_units_at(&self, position: TilePosition, civilization_id: CivilizationId) -> Vec<UnitId> {
+        self.map.tile(position).units
+            .values()
+            .map(|unit_id| *unit_id)
+            .filter(|unit_id| self.units.get(unit_id).unwrap().owner() != civilization_id)
+            .collect()
+    }
+
+    fn in_friendly_territory(&self, unit: &Unit) -> bool {
+        self.map.tile(unit.position()).territory.as_ref()
+            .map(|territory| self.city(territory.city_id).unwrap().owner() == unit.owner())
+            .unwrap_or(false)
+    }
+
+    // Health the unit restores at the start of a turn if it didn't use any movement.
+    pub fn unit_healing(&self, unit: &Unit) -> u16 {
+        let base = if self.in_friendly_territory(unit) { HEAL_IN_FRIENDLY_TERRITORY } else { HEAL_ELSEWHERE };
+        base + unit.extra_healing()
+    }
+
+    // The template the unit can currently be upgraded to, not counting its cost.
+    pub fn unit_upgrade_target(&self, unit: &Unit) -> Option<&UnitTemplate> {
+        let target_id = self.unit_templates.get(unit.template_id()).upgrades_to?;
+        let target = self.unit_templates.get(target_id);
+        let tech_progress = self.civilization(unit.owner()).unwrap().tech_progress();
+
+        if tech_progress.unlocked_units().contains(&target_id)
+            && target.unit_type == unit.unit_type()
+            && unit.remaining_movement() > 0
+            && self.in_friendly_territory(unit)
+        {
+            Some(target)
+        } else {
+            None
+        }
+    }
+
+    pub fn unit_upgrade_cost(&self, unit: &Unit, target: &UnitTemplate) -> YieldValue {
+        self.unit_templates.get(unit.template_id()).upgrade_cost(target)
+    }
+
+    // Movement the unit spends moving between two adjacent tiles, or None if it can't enter the destination.
+    pub fn unit_movement_cost(&self, unit: &Unit, from: TilePosition, to: TilePosition) -> Option<MapUnit> {
+        if !self.foreign_units_at(to, unit.owner()).is_empty() {
+            return None;
+        }
+
+        let target_city_owner = self.map.tile(to).city.map(|city_id| self.city(city_id).unwrap().owner());
+        if let Some(owner) = target_city_owner {
+            if owner != unit.owner() && (!unit.can_fight() || !self.diplomacy.at_war(unit.owner(), owner) || unit.owner().is_barbarian()) {
+                return None;
+            }
+        }
+
+        // City-states can't sign treaties, so their territory is open to anyone, e.g. to deliver gifted units.
+        if let Some(Territory { city_id, .. }) = self.map.tile(to).territory {
+            let owner = self.city(city_id).unwrap().owner();
+            let city_state = self.civilization(owner).unwrap().is_city_state();
+            if !city_state && !self.diplomacy.state(unit.owner(), owner).allows_passage() {
+                return None;
+            }
+        }
+
+        self.map.movement_cost(from, to, unit.domain(), self.can_embark(unit.owner()))
+    }
+
+    // Soldiers exert a zone of control on the neighbouring tiles of their own domain, i.e. ships only at sea and
+    // land units only on land. Embarked units don't exert any. Units entering the zone lose their remaining movement.
+    pub fn in_enemy_zone_of_control(&self, position: TilePosition, civilization_id: CivilizationId) -> bool {
+        let domain = self.map.tile(position).domain();
+
+        position.direct_neighbors(self.map.width(), self.map.height())
+            .into_iter()
+            .filter(|neighbor| self.map.tile(*neighbor).domain() == domain)
+            .filter_map(|neighbor| self.map.tile(neighbor).units.get(&UnitType::Soldier))
+            .map(|unit_id| self.unit(*unit_id).unwrap())
+            .any(|unit| unit.can_fight() && unit.domain() == domain && self.diplomacy.at_war(civilization_id, unit.owner()))
+    }
+
+    pub fn can_embark(&self, civilization_id: CivilizationId) -> bool {
+        self.civilization(civilization_id).unwrap().tech_progress().embarkation_unlocked()
+    }
+
+    pub fn unit_can_reside(&self, unit: &Unit, position: TilePosition) -> bool {
+        self.map.tile(position).unit_can_reside(&unit.unit_type(), unit.domain(), self.can_embark(unit.owner()))
+    }
+
+    // Tiles the unit can move to this turn, with the movement it would have left.
+    pub fn reachable_tiles(&self, unit: &Unit) -> BTreeMap<TilePosition, MapUnit> {
+        self.map.reachable_tiles(
+            unit.position(),
+            unit.remaining_movement(),
+            |from, to| self.unit_movement_cost(unit, from, to),
+            |position| self.in_enemy_zone_of_control(position, unit.owner()),
+        )
+    }
+
+    // Avoids tiles on the way that the unit can't stop on, e.g. ones holding another unit of the same type.
+    pub fn unit_path(&self, unit: &Unit, destination: TilePosition) -> Option<Vec<TilePosition>> {
+        self.map.shortest_path(
+            unit.position(),
+            destination,
+            unit.remaining_movement(),
+            unit.total_movement(),
+            |from, to| {
+                if to != destination && !self.unit_can_reside(unit, to) {
+                    return None;
+                }
+                self.unit_movement_cost(unit, from, to)
+            },
+            |position| self.in_enemy_zone_of_control(position, unit.owner()),
+        )
+    }
+
     pub fn cities(&self) -> impl Iterator<Item = &City> {
         self.cities.iter().map(|(_, v)| v)
     }
@@ -153,14 +477,25 @@ impl GameWorld {
     }
 
     pub fn new_city(&mut self, owner: CivilizationId, position: TilePosition) -> &mut City {
+        let name = self.city_name_generator.next();
+        self.new_named_city(owner, position, name)
+    }
+
+    fn new_named_city(&mut self, owner: CivilizationId, position: TilePosition, name: String) -> &mut City {
         assert!(self.map.tile(position).city.is_none());
 
         let id = self.city_id_generator.next();
-        let name = self.city_name_generator.next();
 
-        let tech_progress = self.civilizations.get(&owner).unwrap().tech_progress();
-        let args = CityArgs { map: &mut self.map, building_types: &self.building_types, tech_progress, unit_templates: &self.unit_templates };
-        let city = City::new(id, owner, position, name, args);
+        let civilization = self.civilizations.get(&owner).unwrap();
+        let args = CityArgs {
+            map: &mut self.map,
+            building_types: &self.building_types,
+            tech_progress: civilization.tech_progress(),
+            strategic_resources: civilization.strategic_resources(),
+            unit_templates: &self.unit_templates,
+        };
+        let mut city = City::new(id, owner, position, name, args);
+        city.capital = !self.cities().any(|city| city.original_owner() == owner && city.is_capital());
 
         self.cities.insert(id, city);
         self.cities.get_mut(&id).unwrap()
@@ -188,11 +523,33 @@ impl GameWorld {
         self.apply_event(&event);
         result.push(event);
 
+        for civilization_id in self.civilizations.keys().map(|id| *id).collect::<Vec<_>>() {
+            let civilization = self.civilization(civilization_id).unwrap();
+            let minor = civilization.is_barbarian() || civilization.is_city_state();
+            if !minor && civilization.gold() < 0.0.into() {
+                result.extend(self.cut_upkeep(civilization_id));
+            }
+        }
+
         let city_keys = self.cities.keys().map(|k| *k).collect::<Vec<_>>();
         for city_id in city_keys {
             {
+                let city = self.cities.get(&city_id).unwrap();
+                if city.razing() {
+                    let event = if city.population() > 1 {
+                        GameEventType::DecreasePopulationFromRazing { city_id }
+                    } else {
+                        GameEventType::DestroyCity { city_id }
+                    };
+                    result.push(self.apply_event_move(event));
+                    continue;
+                }
+            }
+
+            {
+                let unhappy = self.civilization_happiness(self.cities.get(&city_id).unwrap().owner()) < 0;
                 let city = self.cities.get_mut(&city_id).unwrap();
-                if city.can_increase_population_from_food() {
+                if city.can_increase_population_from_food() && !unhappy {
                     let event = GameEventType::IncreasePopulationFromFood { city_id };
                     self.apply_event(&event);
                     result.push(event);
@@ -225,38 +582,33 @@ impl GameWorld {
             };
 
             if let Some(finished_production) = finished_production {
-                match finished_production {
-                    ProducingItem::Unit(template) => {
-                        let city = self.cities.get(&city_id).unwrap();
-
-                        let mut positions_to_try = vec![city.position];
-                        positions_to_try.extend(city.position.direct_neighbors(self.map.width(), self.map.height()));
-
-                        let position = positions_to_try.into_iter()
-                            .find(|pos| self.map.tile(*pos).unit_can_reside(&template.unit_type));
-
-                        if let Some(position) = position {
-                            let owner = city.owner;
-                            let unit_id = self.next_unit_id();
-                            let event = GameEventType::NewUnit { unit_id, template, owner, position };
-                            result.push(self.apply_event_move(event));
-                            let event = GameEventType::SetProducing { city_id, producing: None };
-                            result.push(self.apply_event_move(event));
-                        } else {
-                            let message = "Couldn't find an empty space beside city.";
-                            result.push(GameEventType::Crash { message: message.into() });
-                        }
-                    }
-                    ProducingItem::Building(building_type) => {
-                        let event = GameEventType::NewBuilding { building_type_id: building_type.id, city_id };
-                        result.push(self.apply_event_move(event));
-                        let event = GameEventType::SetProducing { city_id, producing: None };
-                        result.push(self.apply_event_move(event));
-                    }
-                }
+                result.extend(self.finish_production(city_id, finished_production));
             }
         }
 
+        let unit_keys = self.units.keys().map(|k| *k).collect::<Vec<_>>();
+        for unit_id in unit_keys {
+            // Units may have been deleted by an earlier move, e.g. a captured city's civilians.
+            if self.unit(unit_id).map(|unit| unit.has_move_order()).unwrap_or(false) {
+                result.extend(self.advance_move_order(unit_id));
+            }
+        }
+
+        let unit_keys = self.units.keys().map(|k| *k).collect::<Vec<_>>();
+        for unit_id in unit_keys {
+            let finished = self.unit(unit_id)
+                .and_then(|unit| unit.improvement_order())
+                .map(|order| order.remaining_turns == 0)
+                .unwrap_or(false);
+            if finished {
+                result.extend(self.finish_improvement(unit_id));
+            }
+        }
+
+        result.extend(self.spawn_barbarians());
+        result.extend(self.move_barbarians());
+        result.extend(self.wake_units_near_enemies());
+
         for civilization_id in self.civilizations.keys().map(|id| *id).collect::<Vec<_>>() {
             let civilization = self.civilizations.get_mut(&civilization_id).unwrap();
             if civilization.tech_progress.can_finish_research(&self.tech_tree) {
@@ -265,109 +617,124 @@ impl GameWorld {
             }
         }
 
+        if let Some(GameOutcome { winners, victory_type }) = self.check_victory() {
+            let event = GameEventType::GameOver { winners, victory_type };
+            result.push(self.apply_event_move(event));
+        }
+
         result
     }
 
-    pub fn process_action(&mut self, action_type: &GameActionType, actioner_id: PlayerId) -> Vec<GameEventType> {
-        let mut result = Vec::new();
-
-        match action_type {
-            GameActionType::MoveUnit { unit_id, position } => {
-                let unit = if let Some(unit) = self.unit(*unit_id) { unit } else { return vec![] };
-                if self.player(actioner_id).unwrap().civilization_id() != unit.owner() { return vec![] };
-
-                let target_tile_moveable = self.map.tile(*position).unit_can_reside(&unit.unit_type());
-                let neighbor_map = unit.position().neighbors_at_distance(self.map.width(), self.map.height(), unit.remaining_movement(), true);
-                let distance = neighbor_map.get(position);
-                let target_tile_in_range = distance.is_some();
+    fn can_negotiate_with(&self, civilization_id: CivilizationId, other: CivilizationId) -> bool {
+        civilization_id != other
+            && !other.is_barbarian()
+            && self.civilization(other).map(|civ| !civ.eliminated()).unwrap_or(false)
+    }
 
-                if target_tile_moveable && target_tile_in_range {
-                    let event = GameEventType::MoveUnit {
-                        unit_id: *unit_id,
-                        position: *position,
-                        remaining_movement: unit.remaining_movement() - distance.unwrap(),
-                    };
-                    result.push(self.apply_event_move(event));
-                }
-            }
-            GameActionType::FoundCity { unit_id } => {
-                let unit = if let Some(unit) = self.unit(*unit_id) { unit } else { return vec![] };
-                if self.player(actioner_id).unwrap().civilization_id() != unit.owner() { return vec![] };
-                let city_exists_on_tile = self.map.tile(unit.position()).city.is_some();
+    // Barbarian camps can't appear where players would see them.
+    fn can_place_barbarian_camp(&self, tile: &Tile) -> bool {
+        let position = tile.position;
+        tile.resideable()
+            && tile.territory.is_none()
+            && tile.city.is_none()
+            && tile.units.is_empty()
+            && !self.barbarian_camps.contains_key(&position)
+            && self.units()
+                .filter(|unit| !unit.owner().is_barbarian())
+                .all(|unit| unit.position().distance_to(position) > BARBARIAN_SIGHT_RANGE)
+            && self.cities().all(|city| city.position().distance_to(position) > BARBARIAN_SIGHT_RANGE + 1)
+    }
 
-                if unit.has_ability(UnitAbility::Settle) && unit.remaining_movement() >= 1 && !city_exists_on_tile {
-                    let events = vec![
-                        GameEventType::DeleteUnit { unit_id: *unit_id },
-                        GameEventType::FoundCity { position: unit.position(), owner: unit.owner() },
-                    ];
-                    self.apply_events(&events);
-                    result.extend(events);
-                }
-            }
-            GameActionType::RenameCity { city_id, name } => {
-                if let Some(city) = self.city(*city_id) {
-                    if self.player(actioner_id).unwrap().civilization_id() != city.owner() { return vec![] };
+    // New camps appear periodically, and existing camps send out raiders.
+    fn spawn_barbarians(&mut self) -> Vec<GameEventType> {
+        let mut result = vec![];
 
-                    let event = GameEventType::RenameCity { city_id: *city_id, name: name.clone() };
-                    result.push(self.apply_event_move(event));
-                }
-            }
-            GameActionType::SetReady(ready) => {
-                let event = GameEventType::SetPlayerReady{ player_id: actioner_id, ready: *ready };
+        let camp_turn = self.turn >= BarbarianCamp::FIRST_SPAWN_TURN
+            && (self.turn - BarbarianCamp::FIRST_SPAWN_TURN) % BarbarianCamp::SPAWN_INTERVAL == 0;
+        if camp_turn && self.barbarian_camps.len() < BarbarianCamp::max_camps(&self.map) {
+            let positions: Vec<_> = self.map.tiles()
+                .filter(|tile| self.can_place_barbarian_camp(tile))
+                .map(|tile| tile.position)
+                .collect();
+            if !positions.is_empty() {
+                let position = positions[rand::random::<usize>() % positions.len()];
+                let event = GameEventType::NewBarbarianCamp { position };
                 result.push(self.apply_event_move(event));
-
-                if self.players().all(|player| player.ready()) {
-                    result.extend(self.next_turn());
-                }
             }
-            GameActionType::SetProducing { city_id, producing } => {
-                let city = if let Some(city) = self.city(*city_id) { city } else { return vec![] };
-                if self.player(actioner_id).unwrap().civilization_id() != city.owner() { return vec![] };
+        }
 
-                let event = GameEventType::SetProducing { city_id: *city_id, producing: producing.clone() };
+        let template = self.unit_templates.get_by_name("Warrior").clone();
+        let camps: Vec<_> = self.barbarian_camps.values().copied().collect();
+        let mut raider_count = self.units().filter(|unit| unit.owner().is_barbarian()).count();
+        for camp in &camps {
+            let tile = self.map.tile(camp.position);
+            let blocked = !tile.units.is_empty() || tile.city.is_some();
+            if camp.spawns_raider_on(self.turn) && !blocked && raider_count < camps.len() * BarbarianCamp::MAX_RAIDERS {
+                let unit_id = self.next_unit_id();
+                let event = GameEventType::NewUnit {
+                    template: template.clone(),
+                    owner: CivilizationId::BARBARIANS,
+                    position: camp.position,
+                    unit_id,
+                };
                 result.push(self.apply_event_move(event));
+                raider_count += 1;
             }
-            GameActionType::SetSleeping { unit_id, sleeping } => {
-                let unit = if let Some(unit) = self.unit(*unit_id) { unit } else { return vec![] };
-                if self.player(actioner_id).unwrap().civilization_id() != unit.owner() { return vec![] };
+        }
 
-                let event = GameEventType::SetSleeping { unit_id: *unit_id, sleeping: *sleeping };
-                result.push(self.apply_event_move(event));
-            }
-            GameActionType::SetCitizenLocked { city_id, position, locked } => {
-                let city = if let Some(city) = self.city(*city_id) { city } else { return vec![] };
-                if self.player(actioner_id).unwrap().civilization_id() != city.owner() { return vec![] };
-                if !city.territory().contains_key(position) { return vec![] };
+        result
+    }
 
-                let event = GameEventType::SetCitizenLocked { city_id: *city_id, position: *position, locked: *locked };
-                result.push(self.apply_event_move(event));
-            }
-            GameActionType::Harvest { unit_id } => {
-                let unit = if let Some(unit) = self.unit(*unit_id) { unit } else { return vec![] };
-                if self.player(actioner_id).unwrap().civilization_id() != unit.owner() { return vec![] };
-                if unit.can_harvest(&self.cities, &self.map) {
-                    let event = GameEventType::Harvest { position: unit.position() };
-                    result.push(self.apply_event_move(event));
+    // Raiders attack adjacent units, and otherwise head for the nearest city to pillage its gold.
+    fn move_barbarians(&mut self) -> Vec<GameEventType> {
+        let mut result = vec![];
 
-                    let event = GameEventType::DepleteMovement { unit_id: *unit_id };
-                    result.push(self.apply_event_move(event));
+        let unit_ids: Vec<_> = self.units().filter(|unit| unit.owner().is_barbarian()).map(|unit| unit.id()).collect();
+        for unit_id in unit_ids {
+            let unit = if let Some(unit) = self.unit(unit_id) { unit } else { continue };
+            let neighbors = unit.position().direct_neighbors(self.map.width(), self.map.height());
+
+            let target = neighbors.iter().copied().find(|position| {
+                self.map.tile(*position).domain() == unit.domain()
+                    && !self.foreign_units_at(*position, CivilizationId::BARBARIANS).is_empty()
+            });
+            if let Some(position) = target {
+                result.extend(self.attack(unit_id, position));
+                continue;
+            }
 
-                    let event = GameEventType::UseCharge { unit_id: *unit_id };
+            let city = self.cities()
+                .filter(|city| city.position().distance_to(unit.position()) <= BARBARIAN_RAID_RANGE)
+                .min_by_key(|city| (city.position().distance_to(unit.position()), city.id()))
+                .map(|city| (city.position(), city.owner()));
+            let (city_position, owner) = if let Some(city) = city { city } else { continue };
+
+            if neighbors.contains(&city_position) {
+                if unit.remaining_movement() < 1 { continue };
+                let gold = self.civilization(owner).unwrap().gold();
+                let raid_gold = BarbarianCamp::RAID_GOLD.into();
+                let amount = if gold < raid_gold { gold } else { raid_gold };
+                if amount > 0.0.into() {
+                    let event = GameEventType::SpendGold { civilization_id: owner, amount };
                     result.push(self.apply_event_move(event));
-
-                    let unit = self.unit(*unit_id).unwrap();
-                    if unit.charges().unwrap().0 == 0 {
-                        let event = GameEventType::DeleteUnit { unit_id: *unit_id };
-                        result.push(self.apply_event_move(event));
-                    }
                 }
-            }
-            GameActionType::SetResearch { tech_id } => {
-                let civilization_id = self.player(actioner_id).unwrap().civilization_id();
-                let tech_progress = &self.civilizations.get(&civilization_id).unwrap().tech_progress;
-                if tech_progress.can_research(*tech_id, &self.tech_tree) {
-                    let event = GameEventType::SetResearch { civilization_id, tech_id: *tech_id };
+                let event = GameEventType::DepleteMovement { unit_id };
+                result.push(self.apply_event_move(event));
+            } else {
+                // Path to the city as if it could be entered, then stop beside it.
+                let path = self.map.shortest_path(
+                    unit.position(),
+                    city_position,
+                    unit.remaining_movement(),
+                    unit.total_movement(),
+                    |from, to| if to == city_position { Some(1) } else { self.unit_movement_cost(unit, from, to) },
+                    |position| self.in_enemy_zone_of_control(position, unit.owner()),
+                );
+                if let Some(mut path) = path {
+                    path.pop();
+                    let event = GameEventType::SetMovePath { unit_id, path };
                     result.push(self.apply_event_move(event));
+                    result.extend(self.advance_move_order(unit_id));
                 }
             }
         }
@@ -375,50 +742,840 @@ impl GameWorld {
         result
     }
 
-    pub fn apply_event_move(&mut self, event: GameEventType) -> GameEventType {
-        self.apply_event(&event);
-        event
+    pub fn can_build_improvement(&self, unit: &Unit, improvement_type_id: ImprovementTypeId) -> bool {
+        let tech_progress = self.civilization(unit.owner()).unwrap().tech_progress();
+        tech_progress.unlocked_improvements().contains(&improvement_type_id)
+            && unit.can_build_improvement(self.improvement_types.get(improvement_type_id), &self.cities, &self.map)
     }
 
-    fn on_turn_start(&mut self) {
-        for city in self.cities.values_mut() {
-            let tech_progress = self.civilizations.get(&city.owner()).unwrap().tech_progress();
-            let args = CityArgs { map: &mut self.map, building_types: &self.building_types, tech_progress, unit_templates: &self.unit_templates };
-            city.on_turn_start(args);
-        }
+    fn finish_improvement(&mut self, unit_id: UnitId) -> Vec<GameEventType> {
+        let mut result = vec![];
+        let unit = self.unit(unit_id).unwrap();
+        let improvement_type = self.improvement_types.get(unit.improvement_order().unwrap().improvement_type_id);
 
-        for unit in self.units.values_mut() {
-            unit.on_turn_start();
+        // The tile may have changed hands since the order was given.
+        if !unit.can_build_improvement(improvement_type, &self.cities, &self.map) {
+            let event = GameEventType::SetImprovementOrder { unit_id, improvement_type_id: None };
+            return vec![self.apply_event_move(event)];
         }
 
-        for civilization_id in self.civilizations.keys().map(|id| *id).collect::<Vec<_>>() {
-            let science_yield = self.civilization_science_yield(civilization_id).value;
-            self.civilizations.get_mut(&civilization_id).unwrap().on_turn_start(science_yield);
+        let event = GameEventType::FinishImprovement { unit_id };
+        result.push(self.apply_event_move(event));
+
+        let event = GameEventType::UseCharge { unit_id };
+        result.push(self.apply_event_move(event));
+
+        let unit = self.unit(unit_id).unwrap();
+        if unit.charges().unwrap().0 == 0 {
+            let event = GameEventType::DeleteUnit { unit_id };
+            result.push(self.apply_event_move(event));
         }
+
+        result
     }
 
-    // Re-calculate all game state based on sources of truth. Should be idempotent.
-    fn update(&mut self) {
-        for city in self.cities.values_mut() {
-            let tech_progress = self.civilizations.get(&city.owner()).unwrap().tech_progress();
-            let args = CityArgs { map: &mut self.map, building_types: &self.building_types, tech_progress, unit_templates: &self.unit_templates };
-            city.update(args);
+    // Civilizations that can't pay their upkeep lose their most expensive unit each turn, or their most expensive
+    // building once no unit has any upkeep.
+    fn cut_upkeep(&mut self, civilization_id: CivilizationId) -> Vec<GameEventType> {
+        let unit_id = self.units()
+            .filter(|unit| unit.owner() == civilization_id && unit.maintenance() > 0.0.into())
+            .max_by(|a, b| a.maintenance().partial_cmp(&b.maintenance()).unwrap().then(a.id().cmp(&b.id())))
+            .map(|unit| unit.id());
+
+        if let Some(unit_id) = unit_id {
+            let event = GameEventType::DeleteUnit { unit_id };
+            return vec![self.apply_event_move(event)];
+        }
+
+        let building = self.cities()
+            .filter(|city| city.owner() == civilization_id)
+            .flat_map(|city| city.buildings().map(move |building| (city.id(), building)))
+            .filter(|(_, building)| building.maintenance > 0.0.into())
+            .max_by(|(a_city, a), (b_city, b)| {
+                a.maintenance.partial_cmp(&b.maintenance).unwrap().then(a_city.cmp(b_city)).then(a.id.cmp(&b.id))
+            })
+            .map(|(city_id, building)| (city_id, building.id));
+
+        if let Some((city_id, building_type_id)) = building {
+            let event = GameEventType::RemoveBuilding { building_type_id, city_id };
+            vec![self.apply_event_move(event)]
+        } else {
+            vec![]
         }
     }
 
-    pub fn apply_event(&mut self, event_type: &GameEventType) {
-        match event_type {
-            GameEventType::NextTurn => {
-                self.turn += 1;
-                self.on_turn_start();
+    // Where a unit produced in the city would be placed, if there is room for it.
+    fn unit_spawn_position(&self, city_id: CityId, template: &UnitTemplate) -> Option<TilePosition> {
+        let city = self.cities.get(&city_id).unwrap();
 
-                for player in self.players.values_mut() {
-                    player.ready = false;
-                }
-            }
+        let mut positions_to_try = vec![city.position];
+        positions_to_try.extend(city.position.direct_neighbors(self.map.width(), self.map.height()));
+
+        positions_to_try.into_iter()
+            .find(|pos| self.map.tile(*pos).unit_can_reside(&template.unit_type, template.domain, false))
+    }
+
+    fn producing_item(&self, id: &ProducingItemId) -> ProducingItem {
+        match id {
+            ProducingItemId::Unit(id) => ProducingItem::Unit(self.unit_templates.get(*id).clone()),
+            ProducingItemId::Building(id) => ProducingItem::Building(self.building_types.get(*id).clone()),
+        }
+    }
+
+    // Starts the first queued item that can still be produced, dropping any that can't.
+    fn start_next_queued_production(&mut self, city_id: CityId) -> Vec<GameEventType> {
+        let mut result = vec![];
+
+        while let Some(item) = self.cities.get(&city_id).unwrap().production_queue.front() {
+            let item = item.id();
+            let can_produce = self.cities.get(&city_id).unwrap().can_produce(&item);
+
+            let event = GameEventType::RemoveQueuedProduction { city_id, index: 0 };
+            result.push(self.apply_event_move(event));
+
+            if can_produce {
+                let event = GameEventType::SetProducing { city_id, producing: Some(item) };
+                result.push(self.apply_event_move(event));
+                break;
+            }
+        }
+
+        result
+    }
+
+    // True when the city has finished its item but the owner doesn't have the strategic resources it needs.
+    pub fn production_stalled(&self, city_id: CityId) -> bool {
+        let city = self.city(city_id).unwrap();
+        if let Some((producing, spent)) = city.producing() {
+            *spent >= producing.production_cost()
+                && !self.civilization(city.owner()).unwrap().has_strategic_resources(producing.required_resources())
+        } else {
+            false
+        }
+    }
+
+    fn finish_production(&mut self, city_id: CityId, item: ProducingItem) -> Vec<GameEventType> {
+        let mut result = vec![];
+
+        // Production waits until the owner has stockpiled the required resources.
+        let civilization_id = self.cities.get(&city_id).unwrap().owner();
+        let resources = item.required_resources().clone();
+        if !self.civilization(civilization_id).unwrap().has_strategic_resources(&resources) {
+            return result;
+        }
+        if !resources.is_empty() {
+            let event = GameEventType::SpendStrategicResources { civilization_id, resources };
+            result.push(self.apply_event_move(event));
+        }
+
+        match item {
+            ProducingItem::Unit(template) => {
+                if let Some(position) = self.unit_spawn_position(city_id, &template) {
+                    let owner = self.cities.get(&city_id).unwrap().owner;
+                    let unit_id = self.next_unit_id();
+                    let event = GameEventType::NewUnit { unit_id, template, owner, position };
+                    result.push(self.apply_event_move(event));
+                    let event = GameEventType::FinishProducing { city_id };
+                    result.push(self.apply_event_move(event));
+                } else {
+                    let message = "Couldn't find an empty space beside city.";
+                    result.push(GameEventType::Crash { message: message.into() });
+                }
+            }
+            ProducingItem::Building(building_type) => {
+                let event = GameEventType::NewBuilding { building_type_id: building_type.id, city_id };
+                result.push(self.apply_event_move(event));
+                let event = GameEventType::FinishProducing { city_id };
+                result.push(self.apply_event_move(event));
+            }
+        }
+
+        if self.cities.get(&city_id).unwrap().producing().is_none() {
+            result.extend(self.start_next_queued_production(city_id));
+        }
+
+        result
+    }
+
+    // Called after a unit has moved.
+    fn unit_entered_tile(&mut self, unit_id: UnitId) -> Vec<GameEventType> {
+        let mut result = self.clear_barbarian_camp_at_unit(unit_id);
+        result.extend(self.capture_city_at_unit(unit_id));
+        result.extend(self.wake_units_near_enemies());
+        result
+    }
+
+    // Cancels standing orders of units that can see an enemy unit.
+    fn wake_units_near_enemies(&mut self) -> Vec<GameEventType> {
+        let woken: Vec<_> = self.units()
+            .filter(|unit| unit.order().is_some())
+            .filter(|unit| {
+                self.units().any(|other| {
+                    self.diplomacy.at_war(unit.owner(), other.owner())
+                        && unit.position().distance_to(other.position()) <= Unit::SIGHT_RANGE
+                })
+            })
+            .map(|unit| unit.id())
+            .collect();
+
+        woken.into_iter()
+            .map(|unit_id| self.apply_event_move(GameEventType::SetUnitOrder { unit_id, order: None }))
+            .collect()
+    }
+
+    // Soldiers clear barbarian camps by moving into them.
+    fn clear_barbarian_camp_at_unit(&mut self, unit_id: UnitId) -> Vec<GameEventType> {
+        let unit = self.unit(unit_id).unwrap();
+        let position = unit.position();
+
+        if self.barbarian_camps.contains_key(&position) && unit.can_fight() && !unit.owner().is_barbarian() {
+            let events = vec![
+                GameEventType::ClearBarbarianCamp { position, civilization_id: unit.owner() },
+                GameEventType::AddExperience { unit_id, amount: BarbarianCamp::CLEAR_EXPERIENCE },
+            ];
+            self.apply_events(&events);
+            events
+        } else {
+            vec![]
+        }
+    }
+
+    // Soldiers capture enemy cities by moving into them.
+    fn capture_city_at_unit(&mut self, unit_id: UnitId) -> Vec<GameEventType> {
+        let unit = self.unit(unit_id).unwrap();
+        let city_id = if let Some(city_id) = self.map.tile(unit.position()).city { city_id } else { return vec![] };
+
+        if self.city(city_id).unwrap().owner() != unit.owner() && unit.can_fight() {
+            let owner = unit.owner();
+            let mut result = self.cancel_trade_routes(|_, route| route.involves(city_id));
+            let event = GameEventType::CaptureCity { city_id, owner };
+            result.push(self.apply_event_move(event));
+            result
+        } else {
+            vec![]
+        }
+    }
+
+    // Attacks the units at the adjacent position, moving in if every defender is defeated.
+    fn attack(&mut self, unit_id: UnitId, position: TilePosition) -> Vec<GameEventType> {
+        let mut result = vec![];
+
+        let unit = self.unit(unit_id).unwrap();
+        if !unit.can_fight() || unit.remaining_movement() < 1 { return vec![] };
+        if !unit.position().direct_neighbors(self.map.width(), self.map.height()).contains(&position) { return vec![] };
+        // Ships only fight at sea and land units only on land.
+        if self.map.tile(position).domain() != unit.domain() { return vec![] };
+        let civilization_id = unit.owner();
+
+        let foreign_units = self.foreign_units_at(position, civilization_id);
+        if foreign_units.is_empty() { return vec![] };
+        let defending_civilization_id = self.unit(foreign_units[0]).unwrap().owner();
+        if !self.diplomacy.at_war(civilization_id, defending_civilization_id) { return vec![] };
+
+        let defender = foreign_units.iter()
+            .map(|unit_id| self.unit(*unit_id).unwrap())
+            .find(|unit| unit.can_fight());
+        let had_defender = defender.is_some();
+
+        if let Some(defender) = defender {
+            let defender_id = defender.id();
+            let combat = CombatResult::calculate(unit, defender, &self.map);
+
+            let events = vec![
+                GameEventType::DamageUnit { unit_id: defender_id, damage: combat.defender_damage },
+                GameEventType::DamageUnit { unit_id, damage: combat.attacker_damage },
+                GameEventType::AddExperience { unit_id: defender_id, amount: DEFEND_EXPERIENCE },
+                GameEventType::AddExperience { unit_id, amount: ATTACK_EXPERIENCE },
+                GameEventType::DepleteMovement { unit_id },
+            ];
+            self.apply_events(&events);
+            result.extend(events);
+
+            for unit_id in &[defender_id, unit_id] {
+                if self.unit(*unit_id).unwrap().health() == 0 {
+                    let event = GameEventType::DeleteUnit { unit_id: *unit_id };
+                    result.push(self.apply_event_move(event));
+                }
+            }
+        }
+
+        // The attacker moves in if it has defeated every defender.
+        let attacker_alive = self.unit(unit_id).is_some();
+        let defended = self.foreign_units_at(position, civilization_id)
+            .iter()
+            .any(|unit_id| self.unit(*unit_id).unwrap().can_fight());
+        if attacker_alive && !defended {
+            if !had_defender {
+                let event = GameEventType::DepleteMovement { unit_id };
+                result.push(self.apply_event_move(event));
+            }
+
+            for foreign_unit_id in self.foreign_units_at(position, civilization_id) {
+                let event = GameEventType::DeleteUnit { unit_id: foreign_unit_id };
+                result.push(self.apply_event_move(event));
+            }
+
+            // Barbarians raid cities rather than capturing them.
+            let barbarian_into_city = civilization_id.is_barbarian() && self.map.tile(position).city.is_some();
+            if self.unit_can_reside(self.unit(unit_id).unwrap(), position) && !barbarian_into_city {
+                let event = GameEventType::MoveUnit { unit_id, position, remaining_movement: 0 };
+                result.push(self.apply_event_move(event));
+                result.extend(self.unit_entered_tile(unit_id));
+            }
+        }
+
+        result
+    }
+
+    // Moves the unit along its move order as far as its movement allows. If the route has been blocked since it was
+    // planned, a new one is planned, and the order is cancelled if there is none.
+    fn advance_move_order(&mut self, unit_id: UnitId) -> Vec<GameEventType> {
+        let mut result = vec![];
+
+        loop {
+            let unit = self.unit(unit_id).unwrap();
+            let next_position = if let Some(position) = unit.move_path().first() { *position } else { break };
+            if unit.remaining_movement() <= 0 { break };
+
+            let cost = self.unit_movement_cost(unit, unit.position(), next_position);
+            let can_reside = self.unit_can_reside(unit, next_position);
+
+            match cost {
+                Some(cost) if can_reside => {
+                    let event = GameEventType::MoveUnit {
+                        unit_id,
+                        position: next_position,
+                        remaining_movement: (unit.remaining_movement() - cost).max(0),
+                    };
+                    result.push(self.apply_event_move(event));
+                    result.extend(self.unit_entered_tile(unit_id));
+                }
+                _ => {
+                    let destination = *unit.move_path().last().unwrap();
+                    let path = self.unit_path(unit, destination)
+                        .filter(|path| path.first().map(|position| self.unit_can_reside(unit, *position)).unwrap_or(false));
+
+                    if let Some(path) = path {
+                        let event = GameEventType::SetMovePath { unit_id, path };
+                        result.push(self.apply_event_move(event));
+                    } else {
+                        let event = GameEventType::SetMovePath { unit_id, path: vec![] };
+                        result.push(self.apply_event_move(event));
+                        break;
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    fn eliminate_defeated_civilizations(&mut self) -> Vec<GameEventType> {
+        let mut result = vec![];
+
+        for civilization_id in self.civilizations.keys().map(|id| *id).collect::<Vec<_>>() {
+            let defeated = !civilization_id.is_barbarian()
+                && !self.civilization(civilization_id).unwrap().eliminated()
+                && self.cities().all(|city| city.owner() != civilization_id)
+                && self.units().all(|unit| unit.owner() != civilization_id);
+
+            if defeated {
+                let event = GameEventType::EliminateCivilization { civilization_id };
+                result.push(self.apply_event_move(event));
+            }
+        }
+
+        result
+    }
+
+    // Whether the civilization holds the original capital of every other player still in the game.
+    fn has_dominated(&self, civilization_id: CivilizationId) -> bool {
+        self.players()
+            .map(|player| player.civilization_id())
+            .filter(|other| *other != civilization_id && !self.civilization(*other).unwrap().eliminated())
+            .all(|other| {
+                self.cities().any(|city| city.is_capital() && city.original_owner() == other && city.owner() == civilization_id)
+            })
+    }
+
+    fn check_victory(&self) -> Option<GameOutcome> {
+        let conditions = &self.victory_conditions;
+        let contenders: Vec<_> = self.players()
+            .map(|player| player.civilization_id())
+            .filter(|id| !self.civilization(*id).unwrap().eliminated())
+            .collect();
+
+        // There's nobody to dominate in a single player game.
+        if conditions.domination && self.players().count() > 1 {
+            let winners: Vec<_> = contenders.iter().copied().filter(|id| self.has_dominated(*id)).collect();
+            if !winners.is_empty() {
+                return Some(GameOutcome { victory_type: VictoryType::Domination, winners });
+            }
+        }
+
+        if conditions.science {
+            let winners: Vec<_> = contenders.iter()
+                .copied()
+                .filter(|id| !self.civilization(*id).unwrap().tech_progress().can_research_any(&self.tech_tree))
+                .collect();
+            if !winners.is_empty() {
+                return Some(GameOutcome { victory_type: VictoryType::Science, winners });
+            }
+        }
+
+        if conditions.turn_limit.map(|limit| self.turn >= limit).unwrap_or(false) {
+            let best = contenders.iter().map(|id| self.civilization_score(*id)).max()?;
+            let winners = contenders.into_iter().filter(|id| self.civilization_score(*id) == best).collect();
+            return Some(GameOutcome { victory_type: VictoryType::Score, winners });
+        }
+
+        None
+    }
+
+    pub fn process_action(&mut self, action_type: &GameActionType, actioner_id: PlayerId) -> Vec<GameEventType> {
+        let mut result = Vec::new();
+
+        let actioner_civilization_id = self.player(actioner_id).unwrap().civilization_id();
+        if self.civilization(actioner_civilization_id).unwrap().eliminated() { return vec![] };
+        if self.game_over.is_some() { return vec![] };
+
+        match action_type {
+            GameActionType::MoveUnit { unit_id, position } => {
+                let unit = if let Some(unit) = self.unit(*unit_id) { unit } else { return vec![] };
+                if self.player(actioner_id).unwrap().civilization_id() != unit.owner() { return vec![] };
+
+                if !self.unit_can_reside(unit, *position) { return vec![] };
+                let remaining_movement = if let Some(remaining) = self.reachable_tiles(unit).get(position) { *remaining } else { return vec![] };
+
+                let event = GameEventType::MoveUnit { unit_id: *unit_id, position: *position, remaining_movement };
+                result.push(self.apply_event_move(event));
+                result.extend(self.unit_entered_tile(*unit_id));
+            }
+            GameActionType::FoundCity { unit_id } => {
+                let unit = if let Some(unit) = self.unit(*unit_id) { unit } else { return vec![] };
+                if self.player(actioner_id).unwrap().civilization_id() != unit.owner() { return vec![] };
+                let city_exists_on_tile = self.map.tile(unit.position()).city.is_some()
+                    || self.barbarian_camps.contains_key(&unit.position());
+                let tile_resideable = self.map.tile(unit.position()).resideable();
+
+                if unit.has_ability(UnitAbility::Settle) && unit.remaining_movement() >= 1 && !city_exists_on_tile && tile_resideable {
+                    let events = vec![
+                        GameEventType::DeleteUnit { unit_id: *unit_id },
+                        GameEventType::FoundCity { position: unit.position(), owner: unit.owner() },
+                    ];
+                    self.apply_events(&events);
+                    result.extend(events);
+                }
+            }
+            GameActionType::RenameCity { city_id, name } => {
+                if let Some(city) = self.city(*city_id) {
+                    if self.player(actioner_id).unwrap().civilization_id() != city.owner() { return vec![] };
+
+                    let event = GameEventType::RenameCity { city_id: *city_id, name: name.clone() };
+                    result.push(self.apply_event_move(event));
+                }
+            }
+            GameActionType::SetReady(ready) => {
+                let event = GameEventType::SetPlayerReady{ player_id: actioner_id, ready: *ready };
+                result.push(self.apply_event_move(event));
+
+                let all_ready = self.players().all(|player| {
+                    player.ready() || self.civilization(player.civilization_id()).unwrap().eliminated()
+                });
+                if all_ready {
+                    result.extend(self.next_turn());
+                }
+            }
+            GameActionType::SetProducing { city_id, producing } => {
+                let city = if let Some(city) = self.city(*city_id) { city } else { return vec![] };
+                if self.player(actioner_id).unwrap().civilization_id() != city.owner() { return vec![] };
+                if !producing.as_ref().map(|item| city.can_produce(item)).unwrap_or(true) { return vec![] };
+
+                let event = GameEventType::SetProducing { city_id: *city_id, producing: *producing };
+                result.push(self.apply_event_move(event));
+            }
+            GameActionType::EnqueueProduction { city_id, item } => {
+                let city = if let Some(city) = self.city(*city_id) { city } else { return vec![] };
+                if self.player(actioner_id).unwrap().civilization_id() != city.owner() { return vec![] };
+                if !city.can_produce(item) { return vec![] };
+
+                // An idle city starts on the item straight away.
+                let event = if city.producing().is_none() {
+                    GameEventType::SetProducing { city_id: *city_id, producing: Some(*item) }
+                } else {
+                    GameEventType::EnqueueProduction { city_id: *city_id, item: *item }
+                };
+                result.push(self.apply_event_move(event));
+            }
+            GameActionType::MoveQueuedProduction { city_id, from, to } => {
+                let city = if let Some(city) = self.city(*city_id) { city } else { return vec![] };
+                if self.player(actioner_id).unwrap().civilization_id() != city.owner() { return vec![] };
+                let queue_len = city.production_queue().len();
+                if *from >= queue_len || *to >= queue_len { return vec![] };
+
+                let event = GameEventType::MoveQueuedProduction { city_id: *city_id, from: *from, to: *to };
+                result.push(self.apply_event_move(event));
+            }
+            GameActionType::RemoveQueuedProduction { city_id, index } => {
+                let city = if let Some(city) = self.city(*city_id) { city } else { return vec![] };
+                if self.player(actioner_id).unwrap().civilization_id() != city.owner() { return vec![] };
+                if *index >= city.production_queue().len() { return vec![] };
+
+                let event = GameEventType::RemoveQueuedProduction { city_id: *city_id, index: *index };
+                result.push(self.apply_event_move(event));
+            }
+            GameActionType::SetUnitOrder { unit_id, order } => {
+                let unit = if let Some(unit) = self.unit(*unit_id) { unit } else { return vec![] };
+                if self.player(actioner_id).unwrap().civilization_id() != unit.owner() { return vec![] };
+                if !order.map(|order| unit.can_take_order(order)).unwrap_or(true) { return vec![] };
+
+                let event = GameEventType::SetUnitOrder { unit_id: *unit_id, order: *order };
+                result.push(self.apply_event_move(event));
+            }
+            GameActionType::UpgradeUnit { unit_id } => {
+                let unit = if let Some(unit) = self.unit(*unit_id) { unit } else { return vec![] };
+                let civilization_id = unit.owner();
+                if self.player(actioner_id).unwrap().civilization_id() != civilization_id { return vec![] };
+
+                let template = if let Some(template) = self.unit_upgrade_target(unit) { template.clone() } else { return vec![] };
+                let cost = self.unit_upgrade_cost(unit, &template);
+                let civilization = self.civilization(civilization_id).unwrap();
+                if civilization.gold() < cost { return vec![] };
+                if !civilization.has_strategic_resources(&template.required_resources) { return vec![] };
+
+                let event = GameEventType::SpendGold { civilization_id, amount: cost };
+                result.push(self.apply_event_move(event));
+                if !template.required_resources.is_empty() {
+                    let event = GameEventType::SpendStrategicResources { civilization_id, resources: template.required_resources.clone() };
+                    result.push(self.apply_event_move(event));
+                }
+                let event = GameEventType::UpgradeUnit { unit_id: *unit_id, template };
+                result.push(self.apply_event_move(event));
+                let event = GameEventType::DepleteMovement { unit_id: *unit_id };
+                result.push(self.apply_event_move(event));
+            }
+            GameActionType::PromoteUnit { unit_id, promotion_id } => {
+                let unit = if let Some(unit) = self.unit(*unit_id) { unit } else { return vec![] };
+                if self.player(actioner_id).unwrap().civilization_id() != unit.owner() { return vec![] };
+                if !unit.can_take_promotion(self.promotions.get(*promotion_id)) { return vec![] };
+
+                let event = GameEventType::PromoteUnit { unit_id: *unit_id, promotion_id: *promotion_id };
+                result.push(self.apply_event_move(event));
+            }
+            GameActionType::SetCitizenLocked { city_id, position, locked } => {
+                let city = if let Some(city) = self.city(*city_id) { city } else { return vec![] };
+                if self.player(actioner_id).unwrap().civilization_id() != city.owner() { return vec![] };
+                if !city.territory().contains_key(position) { return vec![] };
+
+                let event = GameEventType::SetCitizenLocked { city_id: *city_id, position: *position, locked: *locked };
+                result.push(self.apply_event_move(event));
+            }
+            GameActionType::Harvest { unit_id } => {
+                let unit = if let Some(unit) = self.unit(*unit_id) { unit } else { return vec![] };
+                if self.player(actioner_id).unwrap().civilization_id() != unit.owner() { return vec![] };
+                if unit.can_harvest(&self.cities, &self.map) {
+                    let event = GameEventType::Harvest { position: unit.position() };
+                    result.push(self.apply_event_move(event));
+
+                    let event = GameEventType::DepleteMovement { unit_id: *unit_id };
+                    result.push(self.apply_event_move(event));
+
+                    let event = GameEventType::UseCharge { unit_id: *unit_id };
+                    result.push(self.apply_event_move(event));
+
+                    let unit = self.unit(*unit_id).unwrap();
+                    if unit.charges().unwrap().0 == 0 {
+                        let event = GameEventType::DeleteUnit { unit_id: *unit_id };
+                        result.push(self.apply_event_move(event));
+                    }
+                }
+            }
+            GameActionType::BuildImprovement { unit_id, improvement_type_id } => {
+                let unit = if let Some(unit) = self.unit(*unit_id) { unit } else { return vec![] };
+                if self.player(actioner_id).unwrap().civilization_id() != unit.owner() { return vec![] };
+                if unit.remaining_movement() <= 0 { return vec![] };
+
+                if !self.can_build_improvement(unit, *improvement_type_id) { return vec![] };
+
+                let event = GameEventType::SetImprovementOrder { unit_id: *unit_id, improvement_type_id: Some(*improvement_type_id) };
+                result.push(self.apply_event_move(event));
+
+                let event = GameEventType::DepleteMovement { unit_id: *unit_id };
+                result.push(self.apply_event_move(event));
+            }
+            GameActionType::EstablishTradeRoute { unit_id, destination } => {
+                let unit = if let Some(unit) = self.unit(*unit_id) { unit } else { return vec![] };
+                if self.player(actioner_id).unwrap().civilization_id() != unit.owner() { return vec![] };
+                if !self.trade_route_destinations(unit).contains(destination) { return vec![] };
+
+                let origin = self.map.tile(unit.position()).city.unwrap();
+                let event = GameEventType::EstablishTradeRoute { route: TradeRoute { origin, destination: *destination } };
+                result.push(self.apply_event_move(event));
+
+                let event = GameEventType::DeleteUnit { unit_id: *unit_id };
+                result.push(self.apply_event_move(event));
+            }
+            GameActionType::SetResearch { tech_id } => {
+                let civilization_id = self.player(actioner_id).unwrap().civilization_id();
+                let tech_progress = &self.civilizations.get(&civilization_id).unwrap().tech_progress;
+                if tech_progress.can_research(*tech_id, &self.tech_tree) {
+                    let event = GameEventType::SetResearch { civilization_id, tech_id: *tech_id };
+                    result.push(self.apply_event_move(event));
+                }
+            }
+            GameActionType::Attack { unit_id, position } => {
+                let unit = if let Some(unit) = self.unit(*unit_id) { unit } else { return vec![] };
+                if self.player(actioner_id).unwrap().civilization_id() != unit.owner() { return vec![] };
+
+                result.extend(self.attack(*unit_id, *position));
+            }
+            GameActionType::SetRazing { city_id, razing } => {
+                let city = if let Some(city) = self.city(*city_id) { city } else { return vec![] };
+                if self.player(actioner_id).unwrap().civilization_id() != city.owner() { return vec![] };
+                if !city.can_be_razed() { return vec![] };
+
+                let event = GameEventType::SetRazing { city_id: *city_id, razing: *razing };
+                result.push(self.apply_event_move(event));
+            }
+            GameActionType::PurchaseItem { city_id, item } => {
+                let city = if let Some(city) = self.city(*city_id) { city } else { return vec![] };
+                let civilization_id = city.owner();
+                if self.player(actioner_id).unwrap().civilization_id() != civilization_id { return vec![] };
+
+                // The item is checked so that a purchase can't go through if production changed in the meantime.
+                let producing = if let Some((producing, _)) = city.producing() { producing.clone() } else { return vec![] };
+                if producing.id() != *item { return vec![] };
+
+                let cost = city.purchase_cost().unwrap();
+                if self.civilization(civilization_id).unwrap().gold() < cost { return vec![] };
+                if let ProducingItem::Unit(template) = &producing {
+                    if self.unit_spawn_position(*city_id, template).is_none() { return vec![] };
+                }
+                if !self.civilization(civilization_id).unwrap().has_strategic_resources(producing.required_resources()) { return vec![] };
+
+                let event = GameEventType::SpendGold { civilization_id, amount: cost };
+                result.push(self.apply_event_move(event));
+                result.extend(self.finish_production(*city_id, producing));
+            }
+            GameActionType::BuyTile { city_id, position } => {
+                let city = if let Some(city) = self.city(*city_id) { city } else { return vec![] };
+                let civilization_id = city.owner();
+                if self.player(actioner_id).unwrap().civilization_id() != civilization_id { return vec![] };
+
+                let cost = if let Some(cost) = city.tile_purchase_cost(*position, &self.map) { cost } else { return vec![] };
+                if self.civilization(civilization_id).unwrap().gold() < cost { return vec![] };
+
+                let event = GameEventType::SpendGold { civilization_id, amount: cost };
+                result.push(self.apply_event_move(event));
+                let event = GameEventType::BuyTile { city_id: *city_id, position: *position };
+                result.push(self.apply_event_move(event));
+            }
+            GameActionType::DeclareWar { civilization_id } => {
+                let actioner_civilization_id = self.player(actioner_id).unwrap().civilization_id();
+                if !self.can_negotiate_with(actioner_civilization_id, *civilization_id) { return vec![] };
+                if self.diplomacy.at_war(actioner_civilization_id, *civilization_id) { return vec![] };
+
+                let event = GameEventType::SetDiplomaticState {
+                    civilization_a: actioner_civilization_id,
+                    civilization_b: *civilization_id,
+                    state: DiplomaticState::War,
+                };
+                result.push(self.apply_event_move(event));
+
+                let (a, b) = (actioner_civilization_id, *civilization_id);
+                result.extend(self.cancel_trade_routes(|world, route| {
+                    let owners = world.trade_route_owners(route);
+                    owners == (a, b) || owners == (b, a)
+                }));
+            }
+            GameActionType::ProposeDiplomaticState { civilization_id, state } => {
+                let actioner_civilization_id = self.player(actioner_id).unwrap().civilization_id();
+                if !self.can_negotiate_with(actioner_civilization_id, *civilization_id) { return vec![] };
+                if !state.can_propose_from(self.diplomacy.state(actioner_civilization_id, *civilization_id)) { return vec![] };
+                // City-states don't sign treaties.
+                if self.civilization(*civilization_id).unwrap().is_city_state() { return vec![] };
+
+                let event = GameEventType::SetDiplomaticProposal {
+                    from: actioner_civilization_id,
+                    to: *civilization_id,
+                    state: Some(*state),
+                };
+                result.push(self.apply_event_move(event));
+            }
+            GameActionType::RespondToProposal { civilization_id, accept } => {
+                let actioner_civilization_id = self.player(actioner_id).unwrap().civilization_id();
+                let state = if let Some(state) = self.diplomacy.proposal(*civilization_id, actioner_civilization_id) {
+                    state
+                } else {
+                    return vec![];
+                };
+
+                let event = if *accept {
+                    GameEventType::SetDiplomaticState {
+                        civilization_a: *civilization_id,
+                        civilization_b: actioner_civilization_id,
+                        state,
+                    }
+                } else {
+                    GameEventType::SetDiplomaticProposal {
+                        from: *civilization_id,
+                        to: actioner_civilization_id,
+                        state: None,
+                    }
+                };
+                result.push(self.apply_event_move(event));
+            }
+            GameActionType::GiftGold { civilization_id } => {
+                let actioner_civilization_id = self.player(actioner_id).unwrap().civilization_id();
+                if !self.can_negotiate_with(actioner_civilization_id, *civilization_id) { return vec![] };
+                if !self.civilization(*civilization_id).unwrap().is_city_state() { return vec![] };
+                if self.diplomacy.at_war(actioner_civilization_id, *civilization_id) { return vec![] };
+                let amount = CITY_STATE_GOLD_GIFT.into();
+                if self.civilization(actioner_civilization_id).unwrap().gold() < amount { return vec![] };
+
+                let events = vec![
+                    GameEventType::SpendGold { civilization_id: actioner_civilization_id, amount },
+                    GameEventType::AddInfluence {
+                        city_state_id: *civilization_id,
+                        civilization_id: actioner_civilization_id,
+                        amount: CITY_STATE_GOLD_GIFT_INFLUENCE,
+                    },
+                ];
+                self.apply_events(&events);
+                result.extend(events);
+            }
+            GameActionType::GiftUnit { unit_id } => {
+                let unit = if let Some(unit) = self.unit(*unit_id) { unit } else { return vec![] };
+                if self.player(actioner_id).unwrap().civilization_id() != unit.owner() { return vec![] };
+                let city_state_id = if let Some(id) = self.city_state_gift_target(unit) { id } else { return vec![] };
+
+                let events = vec![
+                    GameEventType::GiftUnit { unit_id: *unit_id, civilization_id: city_state_id },
+                    GameEventType::AddInfluence {
+                        city_state_id,
+                        civilization_id: unit.owner(),
+                        amount: CITY_STATE_UNIT_GIFT_INFLUENCE,
+                    },
+                ];
+                self.apply_events(&events);
+                result.extend(events);
+            }
+            GameActionType::SetMoveOrder { unit_id, destination } => {
+                let unit = if let Some(unit) = self.unit(*unit_id) { unit } else { return vec![] };
+                if self.player(actioner_id).unwrap().civilization_id() != unit.owner() { return vec![] };
+
+                let path = if let Some(destination) = destination {
+                    if !self.map.has_tile(*destination) { return vec![] };
+                    if !self.map.tile(*destination).passable(unit.domain(), self.can_embark(unit.owner())) { return vec![] };
+                    if let Some(path) = self.unit_path(unit, *destination) { path } else { return vec![] }
+                } else {
+                    vec![]
+                };
+
+                let event = GameEventType::SetMovePath { unit_id: *unit_id, path };
+                result.push(self.apply_event_move(event));
+                result.extend(self.advance_move_order(*unit_id));
+            }
+        }
+
+        result.extend(self.eliminate_defeated_civilizations());
+
+        result
+    }
+
+    pub fn apply_event_move(&mut self, event: GameEventType) -> GameEventType {
+        self.apply_event(&event);
+        event
+    }
+
+    fn on_turn_start(&mut self) {
+        // Unhappy civilizations' cities don't grow, so they don't store food for later either.
+        let unhappy_civilizations: Vec<_> = self.civilizations.keys()
+            .copied()
+            .filter(|civilization_id| self.civilization_happiness(*civilization_id) < 0)
+            .collect();
+
+        for city in self.cities.values_mut() {
+            let unhappy = unhappy_civilizations.contains(&city.owner());
+            let civilization = self.civilizations.get(&city.owner()).unwrap();
+            let args = CityArgs {
+                map: &mut self.map,
+                building_types: &self.building_types,
+                tech_progress: civilization.tech_progress(),
+                strategic_resources: civilization.strategic_resources(),
+                unit_templates: &self.unit_templates,
+            };
+            city.on_turn_start(args, unhappy);
+        }
+
+        // Units that didn't use any movement last turn rested.
+        let healing: Vec<_> = self.units.values()
+            .filter(|unit| unit.remaining_movement() >= unit.total_movement())
+            .map(|unit| (unit.id(), self.unit_healing(unit)))
+            .collect();
+        for (unit_id, health) in healing {
+            self.units.get_mut(&unit_id).unwrap().heal(health);
+        }
+
+        for unit in self.units.values_mut() {
+            unit.on_turn_start();
+        }
+
+        for civilization_id in self.civilizations.keys().map(|id| *id).collect::<Vec<_>>() {
+            let science_yield = self.civilization_science_yield(civilization_id).value;
+            let gold_income = self.civilization_gold_yield(civilization_id).value - self.civilization_maintenance(civilization_id);
+            let strategic_income = self.civilization_strategic_resource_income(civilization_id);
+            self.civilizations.get_mut(&civilization_id).unwrap().on_turn_start(science_yield, gold_income, &strategic_income);
+        }
+
+        // Stockpiles changed, so items with resource requirements may have become producible.
+        self.update();
+
+        let statistics = self.players()
+            .map(|player| (player.civilization_id(), self.civilization_statistics(player.civilization_id())))
+            .collect();
+        self.statistics.record(self.turn, statistics);
+    }
+
+    // Re-calculate all game state based on sources of truth. Should be idempotent.
+    fn update(&mut self) {
+        for city in self.cities.values_mut() {
+            let civilization = self.civilizations.get(&city.owner()).unwrap();
+            let args = CityArgs {
+                map: &mut self.map,
+                building_types: &self.building_types,
+                tech_progress: civilization.tech_progress(),
+                strategic_resources: civilization.strategic_resources(),
+                unit_templates: &self.unit_templates,
+            };
+            city.update(args);
+        }
+    }
+
+    pub fn apply_event(&mut self, event_type: &GameEventType) {
+        match event_type {
+            GameEventType::NextTurn => {
+                self.turn += 1;
+                self.on_turn_start();
+
+                for player in self.players.values_mut() {
+                    player.ready = false;
+                }
+            }
             GameEventType::MoveUnit { unit_id, position, remaining_movement } => {
                 self.set_unit_position(*unit_id, *position);
-                self.units.get_mut(unit_id).unwrap().remaining_movement = *remaining_movement;
+                let zone_of_control = self.in_enemy_zone_of_control(*position, self.unit(*unit_id).unwrap().owner());
+                let unit = self.units.get_mut(unit_id).unwrap();
+                unit.remaining_movement = if zone_of_control { 0 } else { *remaining_movement };
+
+                // Moving anywhere other than along the route cancels the move order.
+                if unit.move_path.first() == Some(position) {
+                    unit.move_path.remove(0);
+                } else {
+                    unit.move_path.clear();
+                }
+                unit.improvement_order = None;
+                unit.cancel_order();
             }
             GameEventType::DeleteUnit { unit_id } => {
                 self.delete_unit(*unit_id);
@@ -433,15 +1590,23 @@ impl GameWorld {
                 self.players.get_mut(player_id).unwrap().ready = *ready;
             }
             GameEventType::SetProducing { city_id, producing } => {
-                let producing = producing.as_ref().map(|producing| match producing {
-                    ProducingItemId::Unit(template) => {
-                        ProducingItem::Unit(template.clone())
-                    }
-                    ProducingItemId::Building(id) => {
-                        ProducingItem::Building(self.building_types.get(*id).clone())
-                    }
-                });
-                self.cities.get_mut(city_id).unwrap().producing = producing.clone().and_then(|x| Some((x, 0.0.into())));
+                let producing = producing.as_ref().map(|producing| self.producing_item(producing));
+                self.cities.get_mut(city_id).unwrap().set_producing(producing);
+            }
+            GameEventType::FinishProducing { city_id } => {
+                self.cities.get_mut(city_id).unwrap().finish_producing();
+            }
+            GameEventType::EnqueueProduction { city_id, item } => {
+                let item = self.producing_item(item);
+                self.cities.get_mut(city_id).unwrap().production_queue.push_back(item);
+            }
+            GameEventType::MoveQueuedProduction { city_id, from, to } => {
+                let queue = &mut self.cities.get_mut(city_id).unwrap().production_queue;
+                let item = queue.remove(*from).unwrap();
+                queue.insert(*to, item);
+            }
+            GameEventType::RemoveQueuedProduction { city_id, index } => {
+                self.cities.get_mut(city_id).unwrap().production_queue.remove(*index);
             }
             GameEventType::NewUnit { template, owner, position, unit_id } => {
                 self.new_unit(*unit_id, &template, *owner, *position);
@@ -449,35 +1614,71 @@ impl GameWorld {
             GameEventType::NewBuilding { city_id, building_type_id } => {
                 self.new_building(*city_id, *building_type_id);
             }
+            GameEventType::RemoveBuilding { city_id, building_type_id } => {
+                self.remove_building(*city_id, *building_type_id);
+            }
             GameEventType::Crash { .. } => {
                 // We expect the client to handle this.
             }
-            GameEventType::SetSleeping { unit_id, sleeping } => {
-                self.units.get_mut(unit_id).unwrap().sleeping = *sleeping;
+            GameEventType::SetUnitOrder { unit_id, order } => {
+                self.units.get_mut(unit_id).unwrap().set_order(*order);
             }
             GameEventType::SetCitizenLocked { city_id, position, locked } => {
                 let city = self.cities.get_mut(city_id).unwrap();
-                let tech_progress = self.civilizations.get(&city.owner()).unwrap().tech_progress();
-                let args = CityArgs { map: &mut self.map, building_types: &self.building_types, tech_progress, unit_templates: &self.unit_templates };
+                let civilization = self.civilizations.get(&city.owner()).unwrap();
+                let args = CityArgs {
+                    map: &mut self.map,
+                    building_types: &self.building_types,
+                    tech_progress: civilization.tech_progress(),
+                    strategic_resources: civilization.strategic_resources(),
+                    unit_templates: &self.unit_templates,
+                };
                 city.set_citizen_locked(*position, *locked, args);
             }
             GameEventType::IncreasePopulationFromFood { city_id } => {
                 let city = self.cities.get_mut(city_id).unwrap();
-                let tech_progress = self.civilizations.get(&city.owner()).unwrap().tech_progress();
-                let args = CityArgs { map: &mut self.map, building_types: &self.building_types, tech_progress, unit_templates: &self.unit_templates };
+                let civilization = self.civilizations.get(&city.owner()).unwrap();
+                let args = CityArgs {
+                    map: &mut self.map,
+                    building_types: &self.building_types,
+                    tech_progress: civilization.tech_progress(),
+                    strategic_resources: civilization.strategic_resources(),
+                    unit_templates: &self.unit_templates,
+                };
                 city.increase_population_from_food(args);
             }
             GameEventType::AddTerritoryToCity { city_id, position } => {
                 let city = self.cities.get_mut(city_id).unwrap();
-                let tech_progress = self.civilizations.get(&city.owner()).unwrap().tech_progress();
-                let args = CityArgs { map: &mut self.map, building_types: &self.building_types, tech_progress, unit_templates: &self.unit_templates };
+                let civilization = self.civilizations.get(&city.owner()).unwrap();
+                let args = CityArgs {
+                    map: &mut self.map,
+                    building_types: &self.building_types,
+                    tech_progress: civilization.tech_progress(),
+                    strategic_resources: civilization.strategic_resources(),
+                    unit_templates: &self.unit_templates,
+                };
+                city.grow_territory(*position, args);
+                city.reset_territory_growth();
+            }
+            GameEventType::BuyTile { city_id, position } => {
+                let city = self.cities.get_mut(city_id).unwrap();
+                let civilization = self.civilizations.get(&city.owner()).unwrap();
+                let args = CityArgs {
+                    map: &mut self.map,
+                    building_types: &self.building_types,
+                    tech_progress: civilization.tech_progress(),
+                    strategic_resources: civilization.strategic_resources(),
+                    unit_templates: &self.unit_templates,
+                };
                 city.grow_territory(*position, args);
             }
             GameEventType::Harvest { position } => {
                 self.map.tile_mut(*position).harvested = true;
             }
             GameEventType::DepleteMovement { unit_id } => {
-                self.units.get_mut(unit_id).unwrap().remaining_movement = 0;
+                let unit = self.units.get_mut(unit_id).unwrap();
+                unit.remaining_movement = 0;
+                unit.cancel_order();
             }
             GameEventType::UseCharge { unit_id } => {
                 self.units.get_mut(unit_id).unwrap().use_charge();
@@ -490,6 +1691,121 @@ impl GameWorld {
             GameEventType::SetResearch { civilization_id, tech_id } => {
                 self.civilizations.get_mut(civilization_id).unwrap().tech_progress.set_researching(Some(*tech_id));
             }
+            GameEventType::DamageUnit { unit_id, damage } => {
+                self.units.get_mut(unit_id).unwrap().take_damage(*damage);
+            }
+            GameEventType::AddExperience { unit_id, amount } => {
+                self.units.get_mut(unit_id).unwrap().add_experience(*amount);
+            }
+            GameEventType::UpgradeUnit { unit_id, template } => {
+                self.units.get_mut(unit_id).unwrap().upgrade(template);
+            }
+            GameEventType::PromoteUnit { unit_id, promotion_id } => {
+                let promotion = self.promotions.get(*promotion_id).clone();
+                self.units.get_mut(unit_id).unwrap().promote(promotion);
+            }
+            GameEventType::CaptureCity { city_id, owner } => {
+                let city = self.cities.get_mut(city_id).unwrap();
+                let civilization = self.civilizations.get(owner).unwrap();
+                let args = CityArgs {
+                    map: &mut self.map,
+                    building_types: &self.building_types,
+                    tech_progress: civilization.tech_progress(),
+                    strategic_resources: civilization.strategic_resources(),
+                    unit_templates: &self.unit_templates,
+                };
+                city.capture(*owner, args);
+            }
+            GameEventType::SetRazing { city_id, razing } => {
+                self.cities.get_mut(city_id).unwrap().razing = *razing;
+            }
+            GameEventType::DecreasePopulationFromRazing { city_id } => {
+                let city = self.cities.get_mut(city_id).unwrap();
+                let civilization = self.civilizations.get(&city.owner()).unwrap();
+                let args = CityArgs {
+                    map: &mut self.map,
+                    building_types: &self.building_types,
+                    tech_progress: civilization.tech_progress(),
+                    strategic_resources: civilization.strategic_resources(),
+                    unit_templates: &self.unit_templates,
+                };
+                city.decrease_population(args);
+            }
+            GameEventType::DestroyCity { city_id } => {
+                self.delete_city(*city_id);
+            }
+            GameEventType::EliminateCivilization { civilization_id } => {
+                self.civilizations.get_mut(civilization_id).unwrap().eliminated = true;
+            }
+            GameEventType::SetMovePath { unit_id, path } => {
+                self.units.get_mut(unit_id).unwrap().move_path = path.clone();
+            }
+            GameEventType::SpendGold { civilization_id, amount } => {
+                self.civilizations.get_mut(civilization_id).unwrap().gold -= *amount;
+            }
+            GameEventType::SetDiplomaticState { civilization_a, civilization_b, state } => {
+                self.diplomacy.set_state(*civilization_a, *civilization_b, *state);
+            }
+            GameEventType::SetDiplomaticProposal { from, to, state } => {
+                self.diplomacy.set_proposal(*from, *to, *state);
+            }
+            GameEventType::AddInfluence { city_state_id, civilization_id, amount } => {
+                let city_state = self.civilizations.get_mut(city_state_id).unwrap();
+                *city_state.influence.entry(*civilization_id).or_insert(0) += *amount;
+            }
+            GameEventType::GiftUnit { unit_id, civilization_id } => {
+                let unit = self.units.get_mut(unit_id).unwrap();
+                unit.owner = *civilization_id;
+                unit.cancel_order();
+                unit.move_path.clear();
+                unit.improvement_order = None;
+            }
+            GameEventType::GameOver { winners, victory_type } => {
+                self.game_over = Some(GameOutcome { victory_type: *victory_type, winners: winners.clone() });
+            }
+            GameEventType::NewBarbarianCamp { position } => {
+                self.barbarian_camps.insert(*position, BarbarianCamp::new(*position, self.turn));
+            }
+            GameEventType::ClearBarbarianCamp { position, civilization_id } => {
+                self.barbarian_camps.remove(position);
+                self.civilizations.get_mut(civilization_id).unwrap().gold += BarbarianCamp::CLEAR_REWARD.into();
+            }
+            GameEventType::EstablishTradeRoute { route } => {
+                self.trade_routes.insert(*route);
+            }
+            GameEventType::CancelTradeRoute { route } => {
+                self.trade_routes.remove(route);
+            }
+            GameEventType::SpendStrategicResources { civilization_id, resources } => {
+                self.civilizations.get_mut(civilization_id).unwrap().spend_strategic_resources(resources);
+                self.update();
+            }
+            GameEventType::SetImprovementOrder { unit_id, improvement_type_id } => {
+                let order = improvement_type_id.map(|improvement_type_id| ImprovementOrder {
+                    improvement_type_id,
+                    remaining_turns: self.improvement_types.get(improvement_type_id).build_turns,
+                });
+                self.units.get_mut(unit_id).unwrap().improvement_order = order;
+            }
+            GameEventType::FinishImprovement { unit_id } => {
+                let unit = self.units.get_mut(unit_id).unwrap();
+                let improvement_type_id = unit.improvement_order.take().unwrap().improvement_type_id;
+                let tile = self.map.tile_mut(unit.position());
+                tile.improvement = Some(self.improvement_types.get(improvement_type_id).clone());
+
+                if let Some(Territory { city_id, .. }) = tile.territory {
+                    let city = self.cities.get_mut(&city_id).unwrap();
+                    let civilization = self.civilizations.get(&city.owner()).unwrap();
+                    let args = CityArgs {
+                        map: &mut self.map,
+                        building_types: &self.building_types,
+                        tech_progress: civilization.tech_progress(),
+                        strategic_resources: civilization.strategic_resources(),
+                        unit_templates: &self.unit_templates,
+                    };
+                    city.update(args);
+                }
+            }
         }
     }
 
@@ -506,6 +1822,15 @@ impl GameWorld {
         self.units.remove(&unit_id);
     }
 
+    fn delete_city(&mut self, city_id: CityId) {
+        let city = self.cities.remove(&city_id).unwrap();
+        self.trade_routes.retain(|route| !route.involves(city_id));
+        for position in city.territory_tiles() {
+            self.map.tile_mut(*position).territory = None;
+        }
+        self.map.tile_mut(city.position()).city = None;
+    }
+
     fn set_unit_position(&mut self, unit_id: UnitId, new_position: TilePosition) {
         let mut unit = self.units.get_mut(&unit_id).unwrap();
 
@@ -522,16 +1847,135 @@ impl GameWorld {
     fn new_building(&mut self, city_id: CityId, building_type_id: BuildingTypeId) {
         let city = self.cities.get_mut(&city_id).unwrap();
         let civ = self.civilizations.get(&city.owner).unwrap();
-        let args = CityArgs { map: &mut self.map, building_types: &self.building_types, tech_progress: civ.tech_progress(), unit_templates: &self.unit_templates };
+        let args = CityArgs {
+            map: &mut self.map,
+            building_types: &self.building_types,
+            tech_progress: civ.tech_progress(),
+            strategic_resources: civ.strategic_resources(),
+            unit_templates: &self.unit_templates,
+        };
         city.add_building(self.building_types.get(building_type_id).clone(), args);
     }
 
+    fn remove_building(&mut self, city_id: CityId, building_type_id: BuildingTypeId) {
+        let city = self.cities.get_mut(&city_id).unwrap();
+        let civ = self.civilizations.get(&city.owner).unwrap();
+        let args = CityArgs {
+            map: &mut self.map,
+            building_types: &self.building_types,
+            tech_progress: civ.tech_progress(),
+            strategic_resources: civ.strategic_resources(),
+            unit_templates: &self.unit_templates,
+        };
+        city.remove_building(building_type_id, args);
+    }
+
     pub fn civilization_science_yield(&self, civ_id: CivilizationId) -> Yield {
         let mut sum = Yield { yield_type: YieldType::Science, value: 0.0.into() };
 
         for city in self.cities().filter(|city| city.owner() == civ_id) {
-            sum.value += city.yields().science;
+            sum.value += city.yields().science + self.city_trade_yields(city.id()).science;
+        }
+        sum.value += self.city_state_bonus_yields(civ_id).science;
+
+        sum
+    }
+
+    // Cities stop growing while this is negative.
+    pub fn civilization_happiness(&self, civ_id: CivilizationId) -> isize {
+        let mut luxuries = BTreeSet::new();
+        let mut happiness = BASE_HAPPINESS;
+
+        for city in self.cities().filter(|city| city.owner() == civ_id) {
+            happiness += city.happiness_from_buildings();
+            happiness -= city.population() as isize + UNHAPPINESS_PER_CITY;
+
+            for position in city.territory_tiles() {
+                if let Some(resource) = self.map.tile(*position).resource {
+                    if resource.is_luxury() {
+                        luxuries.insert(resource);
+                    }
+                }
+            }
+        }
+
+        happiness + luxuries.len() as isize * HAPPINESS_PER_LUXURY
+    }
+
+    // Strategic resources added to the stockpile every turn from connected tiles.
+    pub fn civilization_strategic_resource_income(&self, civ_id: CivilizationId) -> BTreeMap<ResourceType, u16> {
+        let mut income = BTreeMap::new();
+
+        for city in self.cities().filter(|city| city.owner() == civ_id) {
+            for position in city.territory_tiles() {
+                if let Some(resource) = self.map.tile(*position).connected_strategic_resource() {
+                    *income.entry(resource).or_insert(0) += STRATEGIC_RESOURCE_PER_TILE;
+                }
+            }
+        }
+
+        income
+    }
+
+    // Gold paid every turn for units and buildings.
+    pub fn civilization_maintenance(&self, civ_id: CivilizationId) -> YieldValue {
+        let mut sum = 0.0.into();
+
+        for unit in self.units().filter(|unit| unit.owner() == civ_id) {
+            sum += unit.maintenance();
+        }
+        for city in self.cities().filter(|city| city.owner() == civ_id) {
+            for building in city.buildings() {
+                sum += building.maintenance;
+            }
+        }
+
+        sum
+    }
+
+    pub fn civilization_statistics(&self, civ_id: CivilizationId) -> CivilizationStatistics {
+        let mut statistics = CivilizationStatistics {
+            score: self.civilization_score(civ_id),
+            techs: self.civilization(civ_id).unwrap().tech_progress().completed().count() as u32,
+            units: self.units().filter(|unit| unit.owner() == civ_id).count() as u32,
+            ..CivilizationStatistics::default()
+        };
+
+        for city in self.cities().filter(|city| city.owner() == civ_id) {
+            statistics.cities += 1;
+            statistics.population += city.population().max(0) as u32;
+            statistics.territory += city.territory().len() as u32;
+            statistics.yields.food += city.yields().food;
+            statistics.yields.production += city.yields().production;
+            statistics.yields.culture += city.yields().culture;
+        }
+        statistics.yields.science = self.civilization_science_yield(civ_id).value;
+        statistics.yields.gold = self.civilization_gold_yield(civ_id).value;
+
+        statistics
+    }
+
+    // Decides the winner when the turn limit is reached.
+    pub fn civilization_score(&self, civ_id: CivilizationId) -> u32 {
+        let mut score = 0;
+
+        for city in self.cities().filter(|city| city.owner() == civ_id) {
+            score += SCORE_PER_CITY;
+            score += SCORE_PER_POPULATION * city.population().max(0) as u32;
+            score += SCORE_PER_TILE * city.territory().len() as u32;
+        }
+        score += SCORE_PER_TECH * self.civilization(civ_id).unwrap().tech_progress().completed().count() as u32;
+
+        score
+    }
+
+    pub fn civilization_gold_yield(&self, civ_id: CivilizationId) -> Yield {
+        let mut sum = Yield { yield_type: YieldType::Gold, value: 0.0.into() };
+
+        for city in self.cities().filter(|city| city.owner() == civ_id) {
+            sum.value += city.yields().gold + self.city_trade_yields(city.id()).gold;
         }
+        sum.value += self.city_state_bonus_yields(civ_id).gold;
 
         sum
     }
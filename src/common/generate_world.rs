@@ -1,5 +1,7 @@
 use crate::common::*;
 
+const CITY_STATE_SPACING: MapUnit = 6;
+
 fn choose_with_weights<T: Copy>(inputs: &[(T, usize)]) -> T {
     assert!(inputs.len() > 0);
 
@@ -91,10 +93,9 @@ impl GameWorld {
             }
         }
 
-        for i in 0..world.civilizations().count() {
-            let civilization_id = world.civilizations().nth(i).unwrap().id();
-
-            let x = world.map.width() as f32 / (world.civilizations().count() as f32 + 1.0) * (i as f32 + 1.0);
+        let mut start_positions = vec![];
+        for i in 0..world.players().count() {
+            let x = world.map.width() as f32 / (world.players().count() as f32 + 1.0) * (i as f32 + 1.0);
             let y = if i % 2 == 0 {
                 world.map.height() as f32 / 3.0
             } else {
@@ -106,11 +107,10 @@ impl GameWorld {
                 world.map.tile_mut(position).tile_type = TileType::Plains;
             }
 
-            let id = world.next_unit_id();
-            world.new_unit(id, &world.unit_templates().get_by_name("Settler").clone(), civilization_id, position);
-            let id = world.next_unit_id();
-            world.new_unit(id, &world.unit_templates().get_by_name("Warrior").clone(), civilization_id, position);
+            start_positions.push(position);
         }
+        world.place_starting_units(start_positions);
+        world.place_city_states();
 
         for _ in 0..(num_tiles / 50) {
             world.generate_river(world.random_tile_position());
@@ -118,4 +118,46 @@ impl GameWorld {
 
         world
     }
+
+    pub fn from_map_file(init_players: Vec<InitPlayer>, map_file: &MapFile) -> Result<Self, String> {
+        map_file.validate(init_players.len())?;
+
+        let mut world = Self::new(map_file.width, map_file.height, init_players);
+        world.map = map_file.to_map();
+        world.place_starting_units(map_file.start_positions.clone());
+        world.place_city_states();
+
+        Ok(world)
+    }
+
+    // City-states are kept away from the players' starting positions and each other.
+    fn place_city_states(&mut self) {
+        let mut positions = self.start_positions.clone();
+
+        for _ in 0..self.players().count() {
+            let position = (0..100)
+                .map(|_| self.random_tile_position())
+                .find(|position| {
+                    self.map.tile(*position).resideable()
+                        && positions.iter().all(|other| other.distance_to(*position) >= CITY_STATE_SPACING)
+                });
+            if let Some(position) = position {
+                self.new_city_state(position, CityStateType::random());
+                positions.push(position);
+            }
+        }
+    }
+
+    fn place_starting_units(&mut self, start_positions: Vec<TilePosition>) {
+        let civilization_ids: Vec<_> = self.players().map(|player| player.civilization_id()).collect();
+
+        for (civilization_id, position) in civilization_ids.into_iter().zip(start_positions.iter()) {
+            let id = self.next_unit_id();
+            self.new_unit(id, &self.unit_templates().get_by_name("Settler").clone(), civilization_id, *position);
+            let id = self.next_unit_id();
+            self.new_unit(id, &self.unit_templates().get_by_name("Warrior").clone(), civilization_id, *position);
+        }
+
+        self.start_positions = start_positions;
+    }
 }
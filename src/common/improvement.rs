@@ -0,0 +1,143 @@
+use std::collections::BTreeMap;
+
+use crate::common::*;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+pub struct ImprovementTypeId(u16);
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ImprovementType {
+    pub id: ImprovementTypeId,
+    pub name: String,
+    // Tiles the improvement can be built on.
+    pub matcher: TileMatcher,
+    pub yields: Yields,
+    pub build_turns: u16,
+}
+
+impl ImprovementType {
+    pub fn can_build_on(&self, tile: &Tile) -> bool {
+        tile.city.is_none()
+            && self.matcher.matches(tile)
+            && tile.improvement.as_ref().map(|improvement| improvement.id != self.id).unwrap_or(true)
+    }
+
+    // Improvements built specifically for a resource make it available to the civilization.
+    pub fn connects_resource(&self, resource_type: ResourceType) -> bool {
+        self.matcher.mentions_resource(resource_type)
+    }
+
+    // TODO move to client code
+    pub fn info(&self) -> String {
+        let mut ret: Vec<String> = Vec::new();
+
+        for &yield_type in &[YieldType::Food, YieldType::Production, YieldType::Science, YieldType::Gold, YieldType::Culture] {
+            let value = self.yields.get(yield_type);
+            if value > 0.0.into() {
+                ret.push(format!("{}", Yield { value, yield_type }));
+            }
+        }
+        ret.push(format!("Built on {}", self.matcher));
+        ret.push(format!("Takes {} turns", self.build_turns));
+
+        ret.join("\n")
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ImprovementTypeIdGenerator {
+    next: u16,
+}
+
+impl ImprovementTypeIdGenerator {
+    pub fn new() -> Self {
+        Self { next: 0 }
+    }
+
+    pub fn next(&mut self) -> ImprovementTypeId {
+        self.next += 1;
+        ImprovementTypeId(self.next)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ImprovementTypes {
+    improvement_types: BTreeMap<ImprovementTypeId, ImprovementType>,
+    generator: ImprovementTypeIdGenerator,
+}
+
+impl ImprovementTypes {
+    pub fn new() -> Self {
+        use ResourceType::*;
+
+        let mut s = Self {
+            improvement_types: BTreeMap::new(),
+            generator: ImprovementTypeIdGenerator::new(),
+        };
+
+        let improvement_types = vec![
+            ImprovementType {
+                id: s.generator.next(),
+                name: "Farm".into(),
+                matcher: TileMatcher::IsType(TileType::Plains),
+                yields: Yields::default().with_food(1.0),
+                build_turns: 3,
+            },
+            ImprovementType {
+                id: s.generator.next(),
+                name: "Pasture".into(),
+                matcher: TileMatcher::Or(vec![
+                    TileMatcher::HasResource(Sheep),
+                    TileMatcher::HasResource(Horses),
+                ]),
+                yields: Yields::default().with_food(1.0).with_production(1.0),
+                build_turns: 3,
+            },
+            ImprovementType {
+                id: s.generator.next(),
+                name: "Camp".into(),
+                matcher: TileMatcher::Or(vec![
+                    TileMatcher::HasVegetation(Vegetation::Forest),
+                    TileMatcher::HasVegetation(Vegetation::Jungle),
+                ]),
+                yields: Yields::default().with_gold(1.0),
+                build_turns: 3,
+            },
+            ImprovementType {
+                id: s.generator.next(),
+                name: "Mine".into(),
+                matcher: TileMatcher::Or(vec![
+                    TileMatcher::HasResource(Iron),
+                    TileMatcher::HasResource(Coal),
+                    TileMatcher::HasResource(Niter),
+                    TileMatcher::HasResource(Gold),
+                    TileMatcher::HasResource(Silver),
+                ]),
+                yields: Yields::default().with_production(2.0),
+                build_turns: 4,
+            },
+        ];
+
+        for x in improvement_types.into_iter() {
+            s.add(x);
+        }
+
+        s
+    }
+
+    fn add(&mut self, improvement_type: ImprovementType) {
+        self.improvement_types.insert(improvement_type.id, improvement_type);
+    }
+
+    pub fn get(&self, id: ImprovementTypeId) -> &ImprovementType {
+        self.improvement_types.get(&id).unwrap()
+    }
+
+    pub fn get_by_name(&self, name: &str) -> &ImprovementType {
+        self.improvement_types.values().find(|it| it.name == name).unwrap()
+    }
+
+    pub fn all(&self) -> impl Iterator<Item = &ImprovementType> {
+        self.improvement_types.values()
+    }
+}
@@ -0,0 +1,109 @@
+use std::collections::BTreeSet;
+
+use crate::common::*;
+
+// A human-editable description of a map, stored as RON.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MapFile {
+    pub width: MapUnit,
+    pub height: MapUnit,
+    pub tiles: Vec<MapFileTile>,
+    pub rivers: Vec<CanonicalEdgePosition>,
+    // One is used per player, in order.
+    pub start_positions: Vec<TilePosition>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MapFileTile {
+    pub position: TilePosition,
+    pub tile_type: TileType,
+    pub resource: Option<ResourceType>,
+    pub vegetation: Option<Vegetation>,
+}
+
+impl MapFile {
+    pub fn from_map(map: &GameMap, start_positions: &[TilePosition]) -> Self {
+        let tiles = map.tiles()
+            .map(|tile| MapFileTile {
+                position: tile.position,
+                tile_type: tile.tile_type,
+                resource: tile.resource,
+                vegetation: tile.vegetation,
+            })
+            .collect();
+
+        Self {
+            width: map.width(),
+            height: map.height(),
+            tiles,
+            rivers: map.rivers().into_iter().collect(),
+            start_positions: start_positions.to_vec(),
+        }
+    }
+
+    pub fn from_ron(s: &str) -> Result<Self, String> {
+        ron::from_str(s).map_err(|error| format!("Failed to parse map file: {}", error))
+    }
+
+    pub fn to_ron(&self) -> String {
+        let config = ron::ser::PrettyConfig::new();
+        ron::ser::to_string_pretty(self, config).expect("map file serialization failed")
+    }
+
+    pub fn validate(&self, num_players: usize) -> Result<(), String> {
+        if self.width <= 0 || self.height <= 0 {
+            return Err(format!("Invalid map size {}x{}", self.width, self.height));
+        }
+
+        let has_tile = |position: TilePosition| {
+            position.x >= 0 && position.y >= 0 && position.x < self.width && position.y < self.height
+        };
+
+        let mut seen = BTreeSet::new();
+        for tile in &self.tiles {
+            if !has_tile(tile.position) {
+                return Err(format!("Tile {} is outside the map", tile.position));
+            }
+            if !seen.insert(tile.position) {
+                return Err(format!("Tile {} is defined more than once", tile.position));
+            }
+        }
+        if seen.len() != self.width as usize * self.height as usize {
+            return Err(format!("Expected {} tiles, found {}", self.width as usize * self.height as usize, seen.len()));
+        }
+
+        if self.start_positions.len() < num_players {
+            return Err(format!("Map has {} start positions but there are {} players", self.start_positions.len(), num_players));
+        }
+        let map = self.to_map();
+        let mut seen_start_positions = BTreeSet::new();
+        for position in &self.start_positions {
+            if !map.has_tile(*position) || !map.tile(*position).resideable() {
+                return Err(format!("Start position {} is not resideable", position));
+            }
+            if !seen_start_positions.insert(*position) {
+                return Err(format!("Start position {} is used more than once", position));
+            }
+        }
+
+        Ok(())
+    }
+
+    // Expects the map file to be validated.
+    pub fn to_map(&self) -> GameMap {
+        let mut map = GameMap::new(self.width, self.height);
+
+        for MapFileTile { position, tile_type, resource, vegetation } in &self.tiles {
+            let tile = map.tile_mut(*position);
+            tile.tile_type = *tile_type;
+            tile.resource = *resource;
+            tile.vegetation = *vegetation;
+        }
+
+        for river in &self.rivers {
+            map.add_river(*river);
+        }
+
+        map
+    }
+}
@@ -50,6 +50,10 @@ impl TileEdge {
         }
     }
 
+    pub fn opposite(self) -> Self {
+        self.clockwise().clockwise().clockwise()
+    }
+
     // Starts from TopLeft and goes clockwise
     pub fn index(self) -> usize {
         use TileEdge::*;
@@ -153,6 +157,18 @@ impl CanonicalEdgePosition {
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct EdgePosition(pub TilePosition, pub TileEdge);
 
+impl EdgePosition {
+    pub fn canonical(self) -> CanonicalEdgePosition {
+        use TileEdge::*;
+        let Self(tile, edge) = self;
+
+        match edge {
+            TopLeft | Top | TopRight => CanonicalEdgePosition(tile, edge.canonical().unwrap()),
+            BottomRight | Bottom | BottomLeft => CanonicalEdgePosition(tile.neighbor(edge), edge.opposite().canonical().unwrap()),
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct TilePosition {
     pub x: MapUnit,
@@ -270,6 +286,11 @@ impl TilePosition {
         }
     }
 
+    // The edge of this tile that borders `other`, if they are adjacent.
+    pub fn edge_to(self, other: Self) -> Option<TileEdge> {
+        TileEdge::ALL.iter().copied().find(|edge| self.neighbor(*edge) == other)
+    }
+
     pub fn top(self) -> Self {
         let Self { x, y } = self;
         Self { x, y: y - 1 }
@@ -1,4 +1,5 @@
 mod game_map;
+mod map_file;
 mod map_position;
 mod city_names;
 mod civilization;
@@ -12,7 +13,16 @@ mod yields;
 mod actions;
 mod events;
 mod building;
+mod improvement;
 mod tech;
+mod combat;
+mod promotion;
+mod diplomacy;
+mod trade_route;
+mod barbarian;
+mod city_state;
+mod victory;
+mod statistics;
 mod game_world;
 
 use std::collections::VecDeque;
@@ -27,6 +37,7 @@ use std::time::Duration;
 use serde::{Serialize, Deserialize};
 
 pub use game_map::*;
+pub use map_file::*;
 pub use map_position::*;
 pub use city_names::*;
 pub use civilization::*;
@@ -40,7 +51,16 @@ pub use yields::*;
 pub use actions::*;
 pub use events::*;
 pub use building::*;
+pub use improvement::*;
 pub use tech::*;
+pub use combat::*;
+pub use promotion::*;
+pub use diplomacy::*;
+pub use trade_route::*;
+pub use barbarian::*;
+pub use city_state::*;
+pub use victory::*;
+pub use statistics::*;
 pub use game_world::*;
 
 pub const SERVER_LISTEN: &str = "0.0.0.0:12351";
@@ -53,18 +73,27 @@ pub struct LobbyInfo {
     pub host: PlayerId,
 }
 
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct GameSettings {
+    // Generate a new map if this is None.
+    pub map: Option<MapFile>,
+    pub victory_conditions: VictoryConditions,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum MessageToClient {
     InitializeWorld{ world: GameWorld, player_id: PlayerId },
     Event(GameEventType),
     LobbyInfo(LobbyInfo),
+    // Sent to the host when the game couldn't be started with their settings.
+    StartFailed(String),
     Kick,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum MessageToServer {
     Hello { name: String },
-    Start,
+    Start(GameSettings),
     Action(GameActionType),
     Quit,
 }
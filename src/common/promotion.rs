@@ -0,0 +1,141 @@
+use std::collections::BTreeMap;
+
+use crate::common::*;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+pub struct PromotionId(u16);
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum PromotionEffect {
+    // Percentage added to combat strength. Terrain bonuses apply to the tile being fought over.
+    CombatBonus { percent: u16, matcher: Option<TileMatcher> },
+    ExtraMovement(MapUnit),
+    // Added to the health restored each turn the unit rests.
+    ExtraHealing(u16),
+}
+
+impl std::fmt::Display for PromotionEffect {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PromotionEffect::CombatBonus { percent, matcher: Some(matcher) } => write!(f, "+{}% Strength on {}", percent, matcher),
+            PromotionEffect::CombatBonus { percent, matcher: None } => write!(f, "+{}% Strength", percent),
+            PromotionEffect::ExtraMovement(movement) => write!(f, "+{} Movement", movement),
+            PromotionEffect::ExtraHealing(health) => write!(f, "+{} Healing per turn", health),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Promotion {
+    pub id: PromotionId,
+    pub name: String,
+    pub effects: Vec<PromotionEffect>,
+    // Has to be taken before this one.
+    pub requires: Option<PromotionId>,
+}
+
+impl Promotion {
+    // TODO move to client code
+    pub fn info(&self) -> String {
+        self.effects.iter().map(|effect| effect.to_string()).collect::<Vec<_>>().join("\n")
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PromotionIdGenerator {
+    next: u16,
+}
+
+impl PromotionIdGenerator {
+    pub fn new() -> Self {
+        Self { next: 0 }
+    }
+
+    pub fn next(&mut self) -> PromotionId {
+        self.next += 1;
+        PromotionId(self.next)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Promotions {
+    promotions: BTreeMap<PromotionId, Promotion>,
+    generator: PromotionIdGenerator,
+}
+
+impl Promotions {
+    pub fn new() -> Self {
+        let mut s = Self {
+            promotions: BTreeMap::new(),
+            generator: PromotionIdGenerator::new(),
+        };
+
+        let combat_1 = s.generator.next();
+        let promotions = vec![
+            Promotion {
+                id: combat_1,
+                name: "Combat I".into(),
+                effects: vec![
+                    PromotionEffect::CombatBonus { percent: 15, matcher: None },
+                ],
+                requires: None,
+            },
+            Promotion {
+                id: s.generator.next(),
+                name: "Combat II".into(),
+                effects: vec![
+                    PromotionEffect::CombatBonus { percent: 15, matcher: None },
+                ],
+                requires: Some(combat_1),
+            },
+            Promotion {
+                id: s.generator.next(),
+                name: "Woodsman".into(),
+                effects: vec![
+                    PromotionEffect::CombatBonus {
+                        percent: 30,
+                        matcher: Some(TileMatcher::Or(vec![
+                            TileMatcher::HasVegetation(Vegetation::Forest),
+                            TileMatcher::HasVegetation(Vegetation::Jungle),
+                        ])),
+                    },
+                ],
+                requires: None,
+            },
+            Promotion {
+                id: s.generator.next(),
+                name: "Mobility".into(),
+                effects: vec![
+                    PromotionEffect::ExtraMovement(1),
+                ],
+                requires: Some(combat_1),
+            },
+            Promotion {
+                id: s.generator.next(),
+                name: "Medic".into(),
+                effects: vec![
+                    PromotionEffect::ExtraHealing(10),
+                ],
+                requires: None,
+            },
+        ];
+
+        for x in promotions.into_iter() {
+            s.add(x);
+        }
+
+        s
+    }
+
+    fn add(&mut self, promotion: Promotion) {
+        self.promotions.insert(promotion.id, promotion);
+    }
+
+    pub fn get(&self, id: PromotionId) -> &Promotion {
+        self.promotions.get(&id).unwrap()
+    }
+
+    pub fn all(&self) -> impl Iterator<Item = &Promotion> {
+        self.promotions.values()
+    }
+}
@@ -55,14 +55,30 @@ impl std::fmt::Display for ResourceType {
 }
 
 impl ResourceType {
+    // Each distinct luxury in a civilization's territory adds happiness.
+    pub fn is_luxury(self) -> bool {
+        match self {
+            Gold | Silver => true,
+            _ => false,
+        }
+    }
+
+    // Strategic resources are stockpiled once connected by an improvement.
+    pub fn is_strategic(self) -> bool {
+        match self {
+            Horses | Iron | Niter | Coal => true,
+            _ => false,
+        }
+    }
+
     pub fn yields(self, harvested: bool) -> Yields {
         if !harvested {
             match self {
                 Sheep => Yields::default().with_production(1.0),
                 Horses => Yields::default().with_production(2.0),
-                Gold => Yields::default().with_production(1.0),
+                Gold => Yields::default().with_gold(2.0),
                 Iron => Yields::default().with_production(1.0),
-                Silver => Yields::default().with_production(1.0),
+                Silver => Yields::default().with_gold(1.0),
                 Niter => Yields::default().with_production(1.0),
                 Coal => Yields::default().with_production(1.0),
                 Wheat => Yields::default().with_food(1.0),
@@ -71,9 +87,9 @@ impl ResourceType {
             match self {
                 Sheep => Yields::default().with_production(2.0),
                 Horses => Yields::default().with_production(3.0),
-                Gold => Yields::default().with_production(2.0),
+                Gold => Yields::default().with_gold(3.0),
                 Iron => Yields::default().with_production(2.0),
-                Silver => Yields::default().with_production(2.0),
+                Silver => Yields::default().with_gold(2.0),
                 Niter => Yields::default().with_production(2.0),
                 Coal => Yields::default().with_production(2.0),
                 Wheat => Yields::default().with_food(2.0),
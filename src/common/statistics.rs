@@ -0,0 +1,132 @@
+use std::collections::BTreeMap;
+
+use serde::{Serialize, Deserialize};
+
+use crate::common::*;
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Statistic {
+    Score,
+    Population,
+    Cities,
+    Territory,
+    Food,
+    Production,
+    Science,
+    Gold,
+    Culture,
+    Techs,
+    Units,
+}
+
+impl Statistic {
+    pub const ALL: &'static [Statistic] = &[
+        Statistic::Score,
+        Statistic::Population,
+        Statistic::Cities,
+        Statistic::Territory,
+        Statistic::Food,
+        Statistic::Production,
+        Statistic::Science,
+        Statistic::Gold,
+        Statistic::Culture,
+        Statistic::Techs,
+        Statistic::Units,
+    ];
+}
+
+impl std::fmt::Display for Statistic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Score => "Score",
+            Self::Population => "Population",
+            Self::Cities => "Cities",
+            Self::Territory => "Territory",
+            Self::Food => "Food",
+            Self::Production => "Production",
+            Self::Science => "Science",
+            Self::Gold => "Gold",
+            Self::Culture => "Culture",
+            Self::Techs => "Techs",
+            Self::Units => "Units",
+        })
+    }
+}
+
+// A snapshot of a civilization, taken at the start of a turn.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CivilizationStatistics {
+    pub score: u32,
+    pub population: u32,
+    pub cities: u32,
+    pub territory: u32,
+    // Per-turn yields, including trade and city-state bonuses.
+    pub yields: Yields,
+    pub techs: u32,
+    pub units: u32,
+}
+
+impl CivilizationStatistics {
+    pub fn get(&self, statistic: Statistic) -> f32 {
+        match statistic {
+            Statistic::Score => self.score as f32,
+            Statistic::Population => self.population as f32,
+            Statistic::Cities => self.cities as f32,
+            Statistic::Territory => self.territory as f32,
+            Statistic::Food => self.yields.food.as_f32(),
+            Statistic::Production => self.yields.production.as_f32(),
+            Statistic::Science => self.yields.science.as_f32(),
+            Statistic::Gold => self.yields.gold.as_f32(),
+            Statistic::Culture => self.yields.culture.as_f32(),
+            Statistic::Techs => self.techs as f32,
+            Statistic::Units => self.units as f32,
+        }
+    }
+}
+
+// Quotes fields that would otherwise break the row, such as names with commas.
+fn csv_field(value: &str) -> String {
+    if value.contains(&[',', '"', '\n'][..]) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// Statistics for every player's civilization, recorded each turn.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct StatisticsHistory {
+    turns: BTreeMap<u16, BTreeMap<CivilizationId, CivilizationStatistics>>,
+}
+
+impl StatisticsHistory {
+    pub(in crate::common) fn record(&mut self, turn: u16, statistics: BTreeMap<CivilizationId, CivilizationStatistics>) {
+        self.turns.insert(turn, statistics);
+    }
+
+    // Values of the statistic for the civilization, oldest first.
+    pub fn series(&self, civilization_id: CivilizationId, statistic: Statistic) -> Vec<f32> {
+        self.turns
+            .values()
+            .filter_map(|statistics| statistics.get(&civilization_id))
+            .map(|statistics| statistics.get(statistic))
+            .collect()
+    }
+
+    // One row per civilization per turn, with a header row.
+    pub fn to_csv(&self, civilization_names: &BTreeMap<CivilizationId, String>) -> String {
+        let mut header = vec!["turn".to_string(), "civilization".to_string()];
+        header.extend(Statistic::ALL.iter().map(|statistic| statistic.to_string().to_lowercase()));
+        let mut lines = vec![header.join(",")];
+
+        for (turn, statistics) in &self.turns {
+            for (civilization_id, civilization_statistics) in statistics {
+                let mut row = vec![turn.to_string(), csv_field(&civilization_names[civilization_id])];
+                row.extend(Statistic::ALL.iter().map(|statistic| civilization_statistics.get(*statistic).to_string()));
+                lines.push(row.join(","));
+            }
+        }
+
+        lines.join("\n") + "\n"
+    }
+}
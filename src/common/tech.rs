@@ -14,6 +14,9 @@ pub struct Tech {
     // Also, should we switch to using string IDs? e.g. `granary`.
     buildings: Vec<BuildingType>,
     units: Vec<UnitTemplateId>,
+    improvements: Vec<ImprovementTypeId>,
+    // Allows land units to move onto water.
+    embarkation: bool,
     cost: YieldValue,
     // TODO should be in client code
     position: (f32, f32),
@@ -37,7 +40,7 @@ impl Tech {
     }
 
     // TODO move to client code
-    pub fn info(&self, unit_templates: &UnitTemplates) -> String {
+    pub fn info(&self, unit_templates: &UnitTemplates, improvement_types: &ImprovementTypes) -> String {
         let mut ret: Vec<String> = Vec::new();
 
         ret.push(format!("Cost: {} Science", self.cost));
@@ -60,6 +63,19 @@ impl Tech {
             ret.push("".into());
         }
 
+        if self.improvements.len() > 0 {
+            ret.push("Improvements:".into());
+            for improvement_type_id in &self.improvements {
+                let improvement_type = improvement_types.get(*improvement_type_id);
+                ret.push(format!("{}", improvement_type.name));
+            }
+            ret.push("".into());
+        }
+
+        if self.embarkation {
+            ret.push("Allows land units to embark".into());
+        }
+
         ret.dedup();
         ret.join("\n").trim_end().to_owned()
     }
@@ -75,6 +91,8 @@ pub struct TechProgress {
     progress: YieldValue,
     unlocked_buildings: BTreeSet<BuildingTypeId>,
     unlocked_units: BTreeSet<UnitTemplateId>,
+    unlocked_improvements: BTreeSet<ImprovementTypeId>,
+    embarkation_unlocked: bool,
 }
 
 impl TechProgress {
@@ -85,10 +103,14 @@ impl TechProgress {
             progress: 0.0.into(),
             unlocked_buildings: BTreeSet::new(),
             unlocked_units: BTreeSet::new(),
+            unlocked_improvements: BTreeSet::new(),
+            embarkation_unlocked: false,
         };
 
         ret.update_unlocked_buildings(tech_tree);
         ret.update_unlocked_units(tech_tree);
+        ret.update_unlocked_improvements(tech_tree);
+        ret.update_embarkation_unlocked(tech_tree);
         ret
     }
 
@@ -114,10 +136,27 @@ impl TechProgress {
             .collect();
     }
 
+    fn update_unlocked_improvements(&mut self, tech_tree: &TechTree) {
+        self.unlocked_improvements = self.completed
+            .iter()
+            .flat_map(|tech_id| {
+                tech_tree.get(*tech_id).improvements.iter().map(|i| *i)
+            })
+            .collect();
+    }
+
+    fn update_embarkation_unlocked(&mut self, tech_tree: &TechTree) {
+        self.embarkation_unlocked = self.completed
+            .iter()
+            .any(|tech_id| tech_tree.get(*tech_id).embarkation);
+    }
+
     pub(in crate::common) fn add_completed(&mut self, tech: TechId, tech_tree: &TechTree) {
         self.completed.insert(tech);
         self.update_unlocked_buildings(tech_tree);
         self.update_unlocked_units(tech_tree);
+        self.update_unlocked_improvements(tech_tree);
+        self.update_embarkation_unlocked(tech_tree);
     }
 
     #[allow(dead_code)]
@@ -141,6 +180,14 @@ impl TechProgress {
         &self.unlocked_units
     }
 
+    pub fn unlocked_improvements(&self) -> &BTreeSet<ImprovementTypeId> {
+        &self.unlocked_improvements
+    }
+
+    pub fn embarkation_unlocked(&self) -> bool {
+        self.embarkation_unlocked
+    }
+
     pub(in crate::common) fn on_turn_start(&mut self, science_yield: YieldValue) {
         self.progress += science_yield;
     }
@@ -220,7 +267,7 @@ impl TechTree {
     }
 
     #[allow(unused_variables)]
-    pub fn generate(buildings: &BuildingTypes, units: &UnitTemplates) -> Self {
+    pub fn generate(buildings: &BuildingTypes, units: &UnitTemplates, improvements: &ImprovementTypes) -> Self {
         let mut tree = Self::new();
 
         let root = tree.add(Tech {
@@ -230,12 +277,17 @@ impl TechTree {
             cost: 0.0.into(),
             buildings: vec![
                 buildings.get_by_name("Granary").clone(),
+                buildings.get_by_name("Monument").clone(),
             ],
             units: vec![
                 units.get_by_name("Settler").id,
                 units.get_by_name("Warrior").id,
                 units.get_by_name("Worker").id,
             ],
+            improvements: vec![
+                improvements.get_by_name("Farm").id,
+            ],
+            embarkation: false,
         });
         tree.initial_techs.insert(root);
         let animal_husbandry = tree.add(Tech {
@@ -246,15 +298,28 @@ impl TechTree {
             buildings: vec![
                 buildings.get_by_name("Stable").clone(),
             ],
-            units: vec![],
+            units: vec![
+                units.get_by_name("Horseman").id,
+            ],
+            improvements: vec![
+                improvements.get_by_name("Pasture").id,
+            ],
+            embarkation: false,
         });
         let b_id = tree.add(Tech {
             name: "Pottery".into(),
             dependencies: vec![animal_husbandry],
             position: (0.4, 0.3),
             cost: 10.0.into(),
-            buildings: vec![],
-            units: vec![],
+            buildings: vec![
+                buildings.get_by_name("Market").clone(),
+                buildings.get_by_name("Amphitheater").clone(),
+            ],
+            units: vec![
+                units.get_by_name("Caravan").id,
+            ],
+            improvements: vec![],
+            embarkation: false,
         });
         let c_id = tree.add(Tech {
             name: "Archery".into(),
@@ -263,14 +328,39 @@ impl TechTree {
             cost: 10.0.into(),
             buildings: vec![],
             units: vec![],
+            improvements: vec![
+                improvements.get_by_name("Camp").id,
+            ],
+            embarkation: false,
         });
         let d_id = tree.add(Tech {
             name: "Bronze Working".into(),
             dependencies: vec![c_id],
             position: (0.6, 0.5),
             cost: 10.0.into(),
+            buildings: vec![
+                buildings.get_by_name("Arena").clone(),
+                buildings.get_by_name("Forge").clone(),
+            ],
+            units: vec![
+                units.get_by_name("Swordsman").id,
+            ],
+            improvements: vec![
+                improvements.get_by_name("Mine").id,
+            ],
+            embarkation: false,
+        });
+        let sailing = tree.add(Tech {
+            name: "Sailing".into(),
+            dependencies: vec![root],
+            position: (0.8, 0.2),
+            cost: 15.0.into(),
             buildings: vec![],
-            units: vec![],
+            units: vec![
+                units.get_by_name("Galley").id,
+            ],
+            improvements: vec![],
+            embarkation: true,
         });
 
         // TODO validate tree
@@ -72,12 +72,14 @@ pub struct Tile {
     pub vegetation: Option<Vegetation>,
 
     pub harvested: bool,
+    pub improvement: Option<ImprovementType>,
 }
 
 pub struct TileYieldContributors {
     pub type_yields: Option<Yields>,
     pub resource_yields: Option<Yields>,
     pub vegetation_yields: Option<Yields>,
+    pub improvement_yields: Option<Yields>,
     pub city_yields: Option<Yields>,
     pub city_effect_yields: Option<Yields>,
 }
@@ -90,16 +92,52 @@ impl Tile {
         }
     }
 
-    pub fn unit_can_reside(&self, unit_type: &UnitType) -> bool {
-        self.resideable() && !self.units.contains_key(unit_type)
+    pub fn is_water(&self) -> bool {
+        self.tile_type == Ocean
+    }
+
+    // The domain of units that are at home here, i.e. without embarking.
+    pub fn domain(&self) -> UnitDomain {
+        if self.is_water() { UnitDomain::Sea } else { UnitDomain::Land }
+    }
+
+    pub fn passable(&self, domain: UnitDomain, can_embark: bool) -> bool {
+        match domain {
+            UnitDomain::Land => self.resideable() || (can_embark && self.is_water()),
+            UnitDomain::Sea => self.is_water(),
+        }
+    }
+
+    pub fn unit_can_reside(&self, unit_type: &UnitType, domain: UnitDomain, can_embark: bool) -> bool {
+        self.passable(domain, can_embark) && !self.units.contains_key(unit_type)
+    }
+
+    // Movement spent entering this tile, or None if it is impassable.
+    pub fn movement_cost(&self, domain: UnitDomain, can_embark: bool) -> Option<MapUnit> {
+        if !self.passable(domain, can_embark) {
+            return None;
+        }
+
+        match self.vegetation {
+            Some(Forest) | Some(Jungle) => Some(2),
+            None => Some(1),
+        }
+    }
+
+    // The strategic resource on this tile, if an improvement connects it.
+    pub fn connected_strategic_resource(&self) -> Option<ResourceType> {
+        let resource = self.resource.filter(|resource| resource.is_strategic())?;
+        let improvement = self.improvement.as_ref()?;
+        if improvement.connects_resource(resource) { Some(resource) } else { None }
     }
 
     pub fn yield_contributors(&self) -> TileYieldContributors {
         TileYieldContributors {
             resource_yields: self.resource.map(|r| r.yields(self.harvested)),
             vegetation_yields: self.vegetation.map(|v| v.yields()),
+            improvement_yields: self.improvement.as_ref().map(|i| i.yields),
             type_yields: if self.city.is_none() { Some(self.tile_type.yields()) } else { None },
-            city_yields: self.city.map(|_| Yields::default().with_food(2.0).with_production(2.0)),
+            city_yields: self.city.map(|_| Yields::default().with_food(2.0).with_production(2.0).with_gold(2.0)),
             city_effect_yields: self.territory.as_ref().map(|t| t.city_effect_yields),
         }
     }
@@ -108,6 +146,7 @@ impl Tile {
         let TileYieldContributors {
             resource_yields,
             vegetation_yields,
+            improvement_yields,
             type_yields,
             city_yields,
             city_effect_yields,
@@ -116,6 +155,7 @@ impl Tile {
         let all = &[
             resource_yields,
             vegetation_yields,
+            improvement_yields,
             type_yields,
             city_yields,
             city_effect_yields,
@@ -163,6 +203,16 @@ impl TileMatcher {
         }
     }
 
+    pub fn mentions_resource(&self, resource_type: ResourceType) -> bool {
+        match self {
+            TileMatcher::IsType(_) | TileMatcher::HasVegetation(_) => false,
+            TileMatcher::HasResource(r) => *r == resource_type,
+            TileMatcher::And(matchers) | TileMatcher::Or(matchers) => {
+                matchers.iter().any(|matcher| matcher.mentions_resource(resource_type))
+            }
+        }
+    }
+
     fn display_inner(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             TileMatcher::IsType(tile_type) => {
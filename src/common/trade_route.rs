@@ -0,0 +1,36 @@
+use crate::common::*;
+
+// Established by a trade unit in the origin city. Both ends earn yields every turn.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TradeRoute {
+    pub origin: CityId,
+    pub destination: CityId,
+}
+
+impl TradeRoute {
+    // Maximum distance between the two cities.
+    pub const RANGE: MapUnit = 10;
+
+    const ORIGIN_BASE_GOLD: f32 = 1.0;
+    const ORIGIN_GOLD_PER_DISTANCE: f32 = 0.25;
+    const ORIGIN_GOLD_PER_DESTINATION_BUILDING: f32 = 0.5;
+    const DESTINATION_BASE_SCIENCE: f32 = 0.5;
+    const DESTINATION_SCIENCE_PER_DISTANCE: f32 = 0.1;
+
+    pub fn involves(&self, city_id: CityId) -> bool {
+        self.origin == city_id || self.destination == city_id
+    }
+
+    // Yields earned by the origin and the destination respectively.
+    pub fn yields(&self, origin: &City, destination: &City) -> (Yields, Yields) {
+        let distance = origin.position().distance_to(destination.position()) as f32;
+        let destination_buildings = destination.buildings().count() as f32;
+
+        let origin_gold = Self::ORIGIN_BASE_GOLD
+            + Self::ORIGIN_GOLD_PER_DISTANCE * distance
+            + Self::ORIGIN_GOLD_PER_DESTINATION_BUILDING * destination_buildings;
+        let destination_science = Self::DESTINATION_BASE_SCIENCE + Self::DESTINATION_SCIENCE_PER_DISTANCE * distance;
+
+        (Yields::default().with_gold(origin_gold), Yields::default().with_science(destination_science))
+    }
+}
@@ -37,6 +37,22 @@ impl std::fmt::Display for UnitType {
     }
 }
 
+// Where a unit can move. Land units can also move onto water once their civilization can embark.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum UnitDomain {
+    Land,
+    Sea,
+}
+
+impl std::fmt::Display for UnitDomain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match *self {
+            UnitDomain::Land => "Land",
+            UnitDomain::Sea => "Sea",
+        })
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, PartialOrd, Ord)]
 pub struct UnitId(u16);
 
@@ -60,22 +76,72 @@ impl UnitIdGenerator {
 pub enum UnitAbility {
     Settle,
     Harvest,
+    BuildImprovements,
+    EstablishTradeRoute,
+}
+
+// Keeps the unit where it is until the order is cancelled or an enemy comes into view.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UnitOrder {
+    Sleep,
+    // Builds up a defence bonus over the following turns.
+    Fortify,
+    // Cancelled once the unit is back at full health.
+    Heal,
+    // Cancelled at the start of the next turn.
+    Skip,
+}
+
+impl std::fmt::Display for UnitOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match *self {
+            UnitOrder::Sleep => "Sleep",
+            UnitOrder::Fortify => "Fortify",
+            UnitOrder::Heal => "Heal",
+            UnitOrder::Skip => "Skip turn",
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct ImprovementOrder {
+    pub improvement_type_id: ImprovementTypeId,
+    pub remaining_turns: u16,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct UnitTemplate {
     pub id: UnitTemplateId,
     pub unit_type: UnitType,
+    pub domain: UnitDomain,
     pub name: String,
     pub movement: MapUnit,
     pub abilities: BTreeSet<UnitAbility>,
     pub production_cost: YieldValue,
+    // Gold paid by the owner every turn.
+    pub maintenance: YieldValue,
     pub initial_charges: Option<usize>,
+    // Strategic resources consumed when the unit is produced.
+    pub required_resources: BTreeMap<ResourceType, u16>,
+    // Units with 0 strength can't attack or defend.
+    pub strength: u16,
+    pub max_health: u16,
+    // The successor existing units can be upgraded to once it is unlocked.
+    pub upgrades_to: Option<UnitTemplateId>,
 }
 
 impl UnitTemplate {
-    pub fn turn_cost(&self, production: YieldValue) -> usize {
-        self.production_cost.div_to_get_turn_count(production)
+    const UPGRADE_BASE_GOLD: f32 = 10.0;
+    const UPGRADE_GOLD_PER_PRODUCTION: f32 = 2.0;
+
+    pub fn turn_cost(&self, production: YieldValue, progress: YieldValue) -> usize {
+        (self.production_cost - progress).div_to_get_turn_count(production)
+    }
+
+    // Paying for the difference in production is a bit cheaper than purchasing the successor outright.
+    pub fn upgrade_cost(&self, target: &UnitTemplate) -> YieldValue {
+        let production_difference = (target.production_cost - self.production_cost).as_f32().max(0.0);
+        (Self::UPGRADE_BASE_GOLD + production_difference * Self::UPGRADE_GOLD_PER_PRODUCTION).into()
     }
 }
 
@@ -99,36 +165,127 @@ impl UnitTemplates {
         x.add(UnitTemplate {
             id: generator.next(),
             unit_type: UnitType::Civilian,
+            domain: UnitDomain::Land,
             name: "Settler".into(),
             movement: 2,
             abilities: vec![UnitAbility::Settle].into_iter().collect(),
             production_cost: 20.0.into(),
+            maintenance: 0.0.into(),
             initial_charges: None,
+            required_resources: BTreeMap::new(),
+            strength: 0,
+            max_health: 100,
+            upgrades_to: None,
         });
 
         x.add(UnitTemplate {
             id: generator.next(),
             unit_type: UnitType::Civilian,
+            domain: UnitDomain::Land,
             name: "Worker".into(),
             movement: 2,
-            abilities: vec![UnitAbility::Harvest].into_iter().collect(),
+            abilities: vec![UnitAbility::Harvest, UnitAbility::BuildImprovements].into_iter().collect(),
             production_cost: 15.0.into(),
+            maintenance: 0.0.into(),
             initial_charges: Some(3),
+            required_resources: BTreeMap::new(),
+            strength: 0,
+            max_health: 100,
+            upgrades_to: None,
         });
 
         x.add(UnitTemplate {
             id: generator.next(),
             unit_type: UnitType::Soldier,
+            domain: UnitDomain::Land,
             name: "Warrior".into(),
             movement: 2,
             abilities: vec![].into_iter().collect(),
             production_cost: 14.0.into(),
+            maintenance: 1.0.into(),
+            initial_charges: None,
+            required_resources: BTreeMap::new(),
+            strength: 8,
+            max_health: 100,
+            upgrades_to: None,
+        });
+
+        x.add(UnitTemplate {
+            id: generator.next(),
+            unit_type: UnitType::Soldier,
+            domain: UnitDomain::Sea,
+            name: "Galley".into(),
+            movement: 3,
+            abilities: vec![].into_iter().collect(),
+            production_cost: 20.0.into(),
+            maintenance: 1.0.into(),
+            initial_charges: None,
+            required_resources: BTreeMap::new(),
+            strength: 7,
+            max_health: 100,
+            upgrades_to: None,
+        });
+
+        x.add(UnitTemplate {
+            id: generator.next(),
+            unit_type: UnitType::Civilian,
+            domain: UnitDomain::Land,
+            name: "Caravan".into(),
+            movement: 2,
+            abilities: vec![UnitAbility::EstablishTradeRoute].into_iter().collect(),
+            production_cost: 30.0.into(),
+            maintenance: 0.0.into(),
+            initial_charges: None,
+            required_resources: BTreeMap::new(),
+            strength: 0,
+            max_health: 100,
+            upgrades_to: None,
+        });
+
+        x.add(UnitTemplate {
+            id: generator.next(),
+            unit_type: UnitType::Soldier,
+            domain: UnitDomain::Land,
+            name: "Horseman".into(),
+            movement: 4,
+            abilities: vec![].into_iter().collect(),
+            production_cost: 22.0.into(),
+            maintenance: 1.0.into(),
+            initial_charges: None,
+            required_resources: vec![(ResourceType::Horses, 1)].into_iter().collect(),
+            strength: 11,
+            max_health: 100,
+            upgrades_to: None,
+        });
+
+        x.add(UnitTemplate {
+            id: generator.next(),
+            unit_type: UnitType::Soldier,
+            domain: UnitDomain::Land,
+            name: "Swordsman".into(),
+            movement: 2,
+            abilities: vec![].into_iter().collect(),
+            production_cost: 25.0.into(),
+            maintenance: 1.0.into(),
             initial_charges: None,
+            required_resources: vec![(ResourceType::Iron, 1)].into_iter().collect(),
+            strength: 14,
+            max_health: 100,
+            upgrades_to: None,
         });
 
+        x.set_upgrade("Warrior", "Swordsman");
+
         x
     }
 
+    // Done after every template has been added, since the successor usually comes later.
+    fn set_upgrade(&mut self, from: &str, to: &str) {
+        let to = self.get_by_name(to).id;
+        let from = self.get_by_name(from).id;
+        self.unit_templates.get_mut(&from).unwrap().upgrades_to = Some(to);
+    }
+
     pub fn all(&self) -> impl Iterator<Item = &UnitTemplate> {
         self.unit_templates.values()
     }
@@ -147,32 +304,65 @@ impl UnitTemplates {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Unit {
     id: UnitId,
+    template_id: UnitTemplateId,
     name: String,
-    owner: CivilizationId,
+    pub(in crate::common) owner: CivilizationId,
     unit_type: UnitType,
+    domain: UnitDomain,
     total_movement: MapUnit,
     abilities: BTreeSet<UnitAbility>,
     // (current, initial)
     charges: Option<(usize, usize)>,
-    pub(in crate::common) sleeping: bool,
+    maintenance: YieldValue,
+    strength: u16,
+    max_health: u16,
+    pub(in crate::common) health: u16,
+    // Gained from fighting. Promotions don't spend it, they just need more of it each time.
+    experience: u16,
+    promotions: Vec<Promotion>,
+    pub(in crate::common) order: Option<UnitOrder>,
+    // Turns spent fortified since the unit last moved or attacked.
+    pub(in crate::common) fortification: u16,
     pub(in crate::common) position: TilePosition,
     pub(in crate::common) remaining_movement: MapUnit,
+    // Remaining route of a multi-turn move order, excluding the current position.
+    pub(in crate::common) move_path: Vec<TilePosition>,
+    // The improvement being built on the current tile, if any.
+    pub(in crate::common) improvement_order: Option<ImprovementOrder>,
 }
 
 impl Unit {
+    // The nth promotion needs 5 * n * (n + 1) experience: 10, 30, 60...
+    const EXPERIENCE_PER_LEVEL: u16 = 5;
+    const MAX_FORTIFICATION: u16 = 2;
+    const FORTIFICATION_BONUS_PER_TURN: f32 = 0.25;
+    // Standing orders are cancelled when an enemy comes within this distance.
+    pub const SIGHT_RANGE: MapUnit = 2;
+
     pub fn new(template: &UnitTemplate, id: UnitId, owner: CivilizationId, position: TilePosition) -> Self {
         Self {
             id,
+            template_id: template.id,
             owner,
             unit_type: template.unit_type,
+            domain: template.domain,
             position,
             total_movement: template.movement,
             name: template.name.clone(),
             abilities: template.abilities.clone(),
             charges: template.initial_charges.map(|n| (n, n)),
+            maintenance: template.maintenance,
+            strength: template.strength,
+            max_health: template.max_health,
+            health: template.max_health,
+            experience: 0,
+            promotions: vec![],
 
             remaining_movement: 0,
-            sleeping: false,
+            order: None,
+            fortification: 0,
+            move_path: Vec::new(),
+            improvement_order: None,
         }
     }
 
@@ -188,6 +378,10 @@ impl Unit {
         self.unit_type
     }
 
+    pub fn domain(&self) -> UnitDomain {
+        self.domain
+    }
+
     pub fn position(&self) -> TilePosition {
         self.position
     }
@@ -196,20 +390,178 @@ impl Unit {
         self.id
     }
 
+    pub fn template_id(&self) -> UnitTemplateId {
+        self.template_id
+    }
+
+    // Experience, promotions and damage carry over to the new template.
+    pub(in crate::common) fn upgrade(&mut self, template: &UnitTemplate) {
+        let damage = self.max_health - self.health;
+        self.template_id = template.id;
+        self.name = template.name.clone();
+        self.unit_type = template.unit_type;
+        self.domain = template.domain;
+        self.total_movement = template.movement;
+        self.abilities = template.abilities.clone();
+        self.charges = template.initial_charges.map(|n| (n, n));
+        self.maintenance = template.maintenance;
+        self.strength = template.strength;
+        self.max_health = template.max_health;
+        self.health = template.max_health.saturating_sub(damage).max(1);
+    }
+
     pub(in crate::common) fn on_turn_start(&mut self) {
         self.remaining_movement = self.total_movement();
+        if let Some(order) = &mut self.improvement_order {
+            order.remaining_turns = order.remaining_turns.saturating_sub(1);
+        }
+
+        match self.order {
+            Some(UnitOrder::Fortify) => self.fortification = (self.fortification + 1).min(Self::MAX_FORTIFICATION),
+            Some(UnitOrder::Heal) if self.health >= self.max_health => self.set_order(None),
+            Some(UnitOrder::Skip) => self.set_order(None),
+            _ => {}
+        }
     }
 
     pub fn total_movement(&self) -> MapUnit {
-        self.total_movement
+        let extra_movement: MapUnit = self.promotion_effects()
+            .map(|effect| match effect {
+                PromotionEffect::ExtraMovement(movement) => *movement,
+                _ => 0,
+            })
+            .sum();
+        self.total_movement + extra_movement
     }
 
     pub fn remaining_movement(&self) -> MapUnit {
         self.remaining_movement
     }
 
-    pub fn sleeping(&self) -> bool {
-        self.sleeping
+    pub fn order(&self) -> Option<UnitOrder> {
+        self.order
+    }
+
+    pub fn can_take_order(&self, order: UnitOrder) -> bool {
+        match order {
+            UnitOrder::Sleep | UnitOrder::Skip => true,
+            UnitOrder::Fortify => self.can_fight(),
+            UnitOrder::Heal => self.health < self.max_health,
+        }
+    }
+
+    // Switching away from Fortify loses the fortification bonus.
+    pub(in crate::common) fn set_order(&mut self, order: Option<UnitOrder>) {
+        if order != Some(UnitOrder::Fortify) {
+            self.fortification = 0;
+        }
+        self.order = order;
+    }
+
+    // Moving or attacking loses the fortification bonus as well as the order.
+    pub(in crate::common) fn cancel_order(&mut self) {
+        self.set_order(None);
+    }
+
+    // As a fraction, like combat_bonus.
+    pub fn fortification_bonus(&self) -> f32 {
+        self.fortification as f32 * Self::FORTIFICATION_BONUS_PER_TURN
+    }
+
+    pub fn move_path(&self) -> &[TilePosition] {
+        &self.move_path
+    }
+
+    pub fn has_move_order(&self) -> bool {
+        !self.move_path.is_empty()
+    }
+
+    pub fn maintenance(&self) -> YieldValue {
+        self.maintenance
+    }
+
+    pub fn strength(&self) -> u16 {
+        self.strength
+    }
+
+    pub fn health(&self) -> u16 {
+        self.health
+    }
+
+    pub fn max_health(&self) -> u16 {
+        self.max_health
+    }
+
+    pub fn can_fight(&self) -> bool {
+        self.strength > 0
+    }
+
+    pub(in crate::common) fn take_damage(&mut self, damage: u16) {
+        self.health = self.health.saturating_sub(damage);
+    }
+
+    pub(in crate::common) fn heal(&mut self, health: u16) {
+        self.health = (self.health + health).min(self.max_health);
+    }
+
+    // Health restored each turn the unit rests, on top of the amount for where it is.
+    pub fn extra_healing(&self) -> u16 {
+        self.promotion_effects()
+            .map(|effect| match effect {
+                PromotionEffect::ExtraHealing(health) => *health,
+                _ => 0,
+            })
+            .sum()
+    }
+
+    pub fn experience(&self) -> u16 {
+        self.experience
+    }
+
+    pub(in crate::common) fn add_experience(&mut self, experience: u16) {
+        self.experience = self.experience.saturating_add(experience);
+    }
+
+    pub fn required_experience_for_promotion(&self) -> u16 {
+        let level = self.promotions.len() as u16 + 1;
+        Self::EXPERIENCE_PER_LEVEL * level * (level + 1)
+    }
+
+    pub fn promotions(&self) -> &[Promotion] {
+        &self.promotions
+    }
+
+    pub fn has_promotion(&self, promotion_id: PromotionId) -> bool {
+        self.promotions.iter().any(|promotion| promotion.id == promotion_id)
+    }
+
+    pub fn can_be_promoted(&self) -> bool {
+        self.can_fight() && self.experience >= self.required_experience_for_promotion()
+    }
+
+    pub fn can_take_promotion(&self, promotion: &Promotion) -> bool {
+        self.can_be_promoted()
+            && !self.has_promotion(promotion.id)
+            && promotion.requires.map(|required| self.has_promotion(required)).unwrap_or(true)
+    }
+
+    pub(in crate::common) fn promote(&mut self, promotion: Promotion) {
+        self.promotions.push(promotion);
+    }
+
+    fn promotion_effects(&self) -> impl Iterator<Item = &PromotionEffect> {
+        self.promotions.iter().flat_map(|promotion| promotion.effects.iter())
+    }
+
+    // As a fraction, e.g. 0.15 for +15%.
+    pub fn combat_bonus(&self, tile: &Tile) -> f32 {
+        let percent: u16 = self.promotion_effects()
+            .map(|effect| match effect {
+                PromotionEffect::CombatBonus { percent, matcher } if matcher.as_ref().map(|matcher| matcher.matches(tile)).unwrap_or(true) => *percent,
+                _ => 0,
+            })
+            .sum();
+        percent as f32 / 100.0
     }
 
     pub fn has_ability(&self, ability: UnitAbility) -> bool {
@@ -236,6 +588,24 @@ impl Unit {
             && self.charges.map(|(charges, _)| charges > 0).unwrap_or(true)
     }
 
+    pub fn improvement_order(&self) -> Option<ImprovementOrder> {
+        self.improvement_order
+    }
+
+    pub fn can_build_improvement(&self, improvement_type: &ImprovementType, cities: &BTreeMap<CityId, City>, map: &GameMap) -> bool {
+        let tile = map.tile(self.position);
+        let city = if let Some(Territory { city_id, .. }) = tile.territory {
+            cities.get(&city_id).unwrap()
+        } else {
+            return false;
+        };
+
+        self.has_ability(UnitAbility::BuildImprovements)
+            && improvement_type.can_build_on(tile)
+            && city.owner() == self.owner()
+            && self.charges.map(|(charges, _)| charges > 0).unwrap_or(true)
+    }
+
     pub fn charges(&self) -> Option<(usize, usize)> {
         self.charges
     }
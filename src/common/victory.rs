@@ -0,0 +1,51 @@
+use serde::{Serialize, Deserialize};
+
+use crate::common::*;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VictoryConditions {
+    // Holding every other player's original capital.
+    pub domination: bool,
+    // Researching the whole tech tree.
+    pub science: bool,
+    // The highest scoring civilizations win once this turn is reached.
+    pub turn_limit: Option<u16>,
+}
+
+impl VictoryConditions {
+    pub const DEFAULT_TURN_LIMIT: u16 = 200;
+}
+
+impl Default for VictoryConditions {
+    fn default() -> Self {
+        Self {
+            domination: true,
+            science: true,
+            turn_limit: Some(Self::DEFAULT_TURN_LIMIT),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum VictoryType {
+    Domination,
+    Science,
+    Score,
+}
+
+impl std::fmt::Display for VictoryType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Domination => "Domination",
+            Self::Science => "Science",
+            Self::Score => "Score",
+        })
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GameOutcome {
+    pub victory_type: VictoryType,
+    // More than one civilization can win at once, e.g. when scores are tied.
+    pub winners: Vec<CivilizationId>,
+}
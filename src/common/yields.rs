@@ -14,6 +14,10 @@ impl YieldValue {
         self.0.round() as usize
     }
 
+    pub fn as_f32(self) -> f32 {
+        self.0
+    }
+
     pub fn div_to_get_turn_count(self, rhs: Self) -> usize {
         let turn_count = (self.0 / rhs.0).ceil();
         if turn_count >= 0.0 {
@@ -109,6 +113,9 @@ pub enum YieldType {
     Food,
     Production,
     Science,
+    Gold,
+    // Accumulates in cities to grow their territory.
+    Culture,
 }
 
 impl std::fmt::Display for YieldType {
@@ -117,6 +124,8 @@ impl std::fmt::Display for YieldType {
             YieldType::Food => "Food",
             YieldType::Production => "Production",
             YieldType::Science => "Science",
+            YieldType::Gold => "Gold",
+            YieldType::Culture => "Culture",
         })
     }
 }
@@ -126,6 +135,8 @@ pub struct Yields {
     pub food: YieldValue,
     pub production: YieldValue,
     pub science: YieldValue,
+    pub gold: YieldValue,
+    pub culture: YieldValue,
 }
 
 impl std::ops::Add for Yields {
@@ -136,6 +147,8 @@ impl std::ops::Add for Yields {
             food: self.food + rhs.food,
             production: self.production + rhs.production,
             science: self.science + rhs.science,
+            gold: self.gold + rhs.gold,
+            culture: self.culture + rhs.culture,
         }
     }
 }
@@ -169,6 +182,8 @@ impl std::ops::Mul for Yields {
             food: self.food * rhs.food,
             production: self.production * rhs.production,
             science: self.science * rhs.science,
+            gold: self.gold * rhs.gold,
+            culture: self.culture * rhs.culture,
         }
     }
 }
@@ -200,6 +215,8 @@ impl Yields {
             YieldType::Food => &mut self.food,
             YieldType::Production => &mut self.production,
             YieldType::Science => &mut self.science,
+            YieldType::Gold => &mut self.gold,
+            YieldType::Culture => &mut self.culture,
         }
     }
 
@@ -209,6 +226,8 @@ impl Yields {
             YieldType::Food => self.food,
             YieldType::Production => self.production,
             YieldType::Science => self.science,
+            YieldType::Gold => self.gold,
+            YieldType::Culture => self.culture,
         }
     }
 
@@ -227,7 +246,17 @@ impl Yields {
         self
     }
 
+    pub fn with_gold(mut self, gold: f32) -> Self {
+        self.gold = gold.into();
+        self
+    }
+
+    pub fn with_culture(mut self, culture: f32) -> Self {
+        self.culture = culture.into();
+        self
+    }
+
     pub fn total(self) -> YieldValue {
-        self.food + self.production + self.science
+        self.food + self.production + self.science + self.gold + self.culture
     }
 }
@@ -95,17 +95,26 @@ impl LobbyServer {
         }
     }
 
-    fn start_game(mut self) -> GameServer {
-        let init_players = self.clients.iter().map(|LobbyClient { player_id, name, .. }| {
+    fn create_world(&self, settings: GameSettings) -> Result<GameWorld, String> {
+        let init_players: Vec<_> = self.clients.iter().map(|LobbyClient { player_id, name, .. }| {
             InitPlayer {
                 id: *player_id,
                 name: name.clone(),
             }
         }).collect();
 
-        let mut game_world = GameWorld::generate(init_players);
+        let mut game_world = if let Some(map_file) = settings.map {
+            GameWorld::from_map_file(init_players, &map_file)?
+        } else {
+            GameWorld::generate(init_players)
+        };
+        game_world.set_victory_conditions(settings.victory_conditions);
         game_world.start();
 
+        Ok(game_world)
+    }
+
+    fn start_game(mut self, game_world: GameWorld) -> GameServer {
         for client in &mut self.clients {
             let initialize_stuff = MessageToClient::InitializeWorld {
                 world: game_world.clone(),
@@ -136,14 +145,14 @@ impl LobbyServer {
                 self.broadcast_player_names();
             }
 
-            let mut start_game = false;
+            let mut start_game = None;
 
             for client in &mut self.clients {
                 if let Some(message) = client.connection.receive_message() {
                     match message {
-                        MessageToServer::Start => {
+                        MessageToServer::Start(settings) => {
                             if client.is_host {
-                                start_game = true;
+                                start_game = Some(settings);
                             } else {
                                 panic!();
                             }
@@ -168,8 +177,14 @@ impl LobbyServer {
                 self.broadcast_player_names();
             }
 
-            if start_game {
-                return Some(self.start_game());
+            if let Some(settings) = start_game {
+                match self.create_world(settings) {
+                    Ok(game_world) => return Some(self.start_game(game_world)),
+                    Err(error) => {
+                        let host = self.clients.iter_mut().find(|client| client.is_host).unwrap();
+                        host.connection.send_message(MessageToClient::StartFailed(error));
+                    }
+                }
             }
 
             std::thread::sleep(Duration::from_millis(10));
@@ -214,7 +229,7 @@ impl GameServer {
                             }
                         }
                         MessageToServer::Hello { .. } |
-                        MessageToServer::Start => panic!("Unexpected message: {:?}", message),
+                        MessageToServer::Start(..) => panic!("Unexpected message: {:?}", message),
                     }
                 }
             }